@@ -1,194 +1,677 @@
-use crate::data_stores::{elastic::store::ElasticStore, redis::store::RedisStore};
+use crate::chat_server::handlers::{
+    instance_message::InstanceMessage, send_client_message::SendClientMessage,
+};
+use crate::constants::{
+    DEFAULT_ROOM, MAX_REPLAY_SIZE, PRESENCE_LEASE_TTL_MILLIS, PRESENCE_REAP_INTERVAL_SECS,
+};
+use crate::data_stores::elastic::store::ElasticStore;
+use crate::data_stores::redis::{presence_leases::lease_channel, store::RedisStore};
+use crate::models::messages::MessageDocument;
+use crate::models::roles::Role;
+use crate::models::rooms::RoomError;
+use crate::models::sessions::SessionPolicy;
+use crate::utils::time_as_millis_since_epoch;
 use actix::prelude::*;
-use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use serde_json::json;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::time::Duration;
+use termtalk_proto::chat::{ChatType, MessageType, Presence, QueueMessage};
+use termtalk_proto::direct_chats::DIRECT_CHANNEL_SEPARATOR;
+use termtalk_proto::protocol::{ErrorCode, ServerEvent};
+use termtalk_proto::rooms::RoomRole;
+use uuid::Uuid;
 
 #[derive(Debug)]
 pub struct ChatServer {
-    pub sessions: HashMap<String, ChatSessionState>,
+    pub sessions: HashMap<String, HashMap<String, ChatSessionState>>,
     pub rooms: HashMap<String, HashSet<String>>,
     pub directs: HashMap<String, HashSet<String>>,
     pub redis: RedisStore,
     pub elastic: ElasticStore,
+    pub scrollback_size: usize,
+    pub session_policy: SessionPolicy,
+    pub instance_id: String,
 }
 
 impl Actor for ChatServer {
     type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(
+            Duration::from_secs(PRESENCE_REAP_INTERVAL_SECS),
+            |act, _| act.reap_presence(),
+        );
+    }
 }
 
 impl ChatServer {
-    pub async fn new(redis: RedisStore, elastic: ElasticStore) -> ChatServer {
+    pub async fn new(
+        redis: RedisStore,
+        elastic: ElasticStore,
+        scrollback_size: usize,
+        session_policy: SessionPolicy,
+        instance_id: String,
+    ) -> ChatServer {
         let directs = HashMap::new();
         let rooms = HashMap::new();
 
+        match elastic.rooms.list_rooms().await {
+            Ok(room_documents) => {
+                for room_document in room_documents {
+                    redis
+                        .rooms_hash_map
+                        .set_room_guid_by_name(&room_document._source.name, &room_document._id);
+                    let room = &room_document._source;
+                    if room.archived_at.is_some() {
+                        redis
+                            .archived_rooms_set
+                            .add_to_archived_rooms_set(&room.name);
+                    }
+                    if !room.created_by.is_empty() {
+                        redis
+                            .room_owners_hash_map
+                            .set_room_owner(&room.name, &room.created_by);
+                    }
+                    for username in &room.moderators {
+                        redis
+                            .room_moderators_set
+                            .add_moderator(&room.name, username);
+                    }
+                    for username in &room.banned {
+                        redis
+                            .room_banned_users_set
+                            .add_banned_user(&room.name, username);
+                    }
+                    for username in &room.muted {
+                        redis
+                            .room_muted_users_set
+                            .add_muted_user(&room.name, username);
+                    }
+                    if room.private {
+                        redis.private_rooms_set.add_to_private_rooms_set(&room.name);
+                    }
+                    for username in &room.members {
+                        redis.room_members_set.add_member(&room.name, username);
+                    }
+                }
+            }
+            Err(error) => log::error!("Failed to load rooms from elastic: {}", error),
+        };
+
+        let default_room_guid = Uuid::new_v4().to_string();
+        if redis
+            .rooms_hash_map
+            .set_room_guid_by_name_if_absent(DEFAULT_ROOM, &default_room_guid)
+        {
+            if let Err(error) = elastic
+                .rooms
+                .create_room(&default_room_guid, DEFAULT_ROOM, "", false)
+                .await
+            {
+                log::error!(
+                    "Failed to persist room {} to elastic: {}",
+                    DEFAULT_ROOM,
+                    error
+                );
+            }
+        }
+
         ChatServer {
             sessions: HashMap::new(),
             rooms,
             directs,
             redis,
             elastic,
+            scrollback_size,
+            session_policy,
+            instance_id,
         }
     }
 
-    fn select_color(&self, msg_type: &MessageType) -> String {
-        match msg_type {
-            MessageType::Direct => "blue".to_owned(),
-            MessageType::Room => "white".to_owned(),
-            MessageType::Whisper => "pink".to_owned(),
-            MessageType::Server => "green".to_owned(),
-        }
+    pub fn user_sessions(&self, username: &str) -> impl Iterator<Item = &ChatSessionState> {
+        self.sessions
+            .get(username)
+            .into_iter()
+            .flat_map(|user_sessions| user_sessions.values())
+    }
+
+    pub fn user_session_mut(
+        &mut self,
+        username: &str,
+        connection_id: &str,
+    ) -> Option<&mut ChatSessionState> {
+        self.sessions
+            .get_mut(username)
+            .and_then(|user_sessions| user_sessions.get_mut(connection_id))
     }
 
-    pub fn send_message_to_room(
+    pub fn user_in_channel(
         &self,
+        username: &str,
         channel_name: &str,
-        sender: &str,
-        message: &str,
-        msg_type: &MessageType,
-    ) {
-        if let Some(channel_state) = self.rooms.get(channel_name) {
-            for username in channel_state {
-                let user_session = self.sessions.get(username).unwrap();
-                let mut formatted_msg = format!("{}: {}", &sender, &message);
-                if *msg_type == MessageType::Server {
-                    formatted_msg = message.to_owned();
+        chat_type: &ChatType,
+    ) -> bool {
+        self.user_sessions(username).any(|user_session| {
+            user_session.chat_type == *chat_type && user_session.channel_name == channel_name
+        })
+    }
+
+    pub fn user_in_channel_anywhere(
+        &self,
+        username: &str,
+        channel_name: &str,
+        chat_type: &ChatType,
+        except_connection_id: &str,
+    ) -> bool {
+        match lease_channel(chat_type, channel_name) {
+            Some(channel) => self.redis.presence_leases.channel_live(
+                username,
+                &channel,
+                except_connection_id,
+                time_as_millis_since_epoch(),
+            ),
+            None => false,
+        }
+    }
+
+    pub fn user_online(&self, username: &str) -> bool {
+        self.redis
+            .presence_leases
+            .user_online(username, time_as_millis_since_epoch())
+    }
+
+    pub fn refresh_presence(&self, username: &str, connection_id: &str) {
+        let user_session = match self
+            .sessions
+            .get(username)
+            .and_then(|user_sessions| user_sessions.get(connection_id))
+        {
+            Some(user_session) => user_session,
+            None => return,
+        };
+        let room = match user_session.chat_type {
+            ChatType::Room => Some(user_session.channel_name.as_str()),
+            _ => None,
+        };
+        let channel = lease_channel(&user_session.chat_type, &user_session.channel_name);
+        self.redis.presence_leases.refresh_lease(
+            username,
+            connection_id,
+            channel.as_deref(),
+            time_as_millis_since_epoch() + PRESENCE_LEASE_TTL_MILLIS,
+        );
+        self.redis.session_instances_hash_map.set_session_instance(
+            username,
+            connection_id,
+            &self.instance_id,
+        );
+        self.redis
+            .users_online_set
+            .add_to_users_online_set(username);
+        if let Some(room) = room {
+            self.redis
+                .rooms_online_users_set
+                .add_user_to_room_set(room, username);
+        }
+    }
+
+    pub fn reap_presence(&self) {
+        let now = time_as_millis_since_epoch();
+        for username in self.redis.users_online_set.users_online() {
+            let expired = self
+                .redis
+                .presence_leases
+                .remove_expired_leases(&username, now);
+            if !expired.is_empty() {
+                log::info!(
+                    "Reaped {} expired presence leases for {}",
+                    expired.len(),
+                    &username
+                );
+            }
+            for connection_id in expired {
+                self.redis
+                    .session_instances_hash_map
+                    .remove_session_instance(&username, &connection_id);
+            }
+            if !self.redis.presence_leases.user_online(&username, now) {
+                self.redis
+                    .users_online_set
+                    .remove_from_users_online_set(&username);
+            }
+        }
+        for room in self.redis.rooms_hash_map.list_rooms() {
+            for username in self.redis.rooms_online_users_set.list_users_in_room(&room) {
+                if !self
+                    .redis
+                    .presence_leases
+                    .live_rooms(&username, now)
+                    .contains(&room)
+                {
+                    self.redis
+                        .rooms_online_users_set
+                        .remove_user_from_room_set(&room, &username);
                 }
+            }
+        }
+    }
+
+    pub fn room_exists(&self, room_name: &str) -> bool {
+        self.redis
+            .rooms_hash_map
+            .get_room_guid_by_name(room_name)
+            .is_some()
+    }
+
+    pub fn room_role(&self, room_name: &str, username: &str) -> RoomRole {
+        if self.redis.room_owners_hash_map.get_room_owner(room_name) == Some(username.to_owned()) {
+            RoomRole::Owner
+        } else if self
+            .redis
+            .room_moderators_set
+            .is_moderator(room_name, username)
+        {
+            RoomRole::Moderator
+        } else {
+            RoomRole::Member
+        }
+    }
+
+    pub fn moderator_room_role(&self, room_name: &str, username: &str, roles: &[Role]) -> RoomRole {
+        let room_role = self.room_role(room_name, username);
+        if Role::Admin.granted_by(roles) {
+            RoomRole::Owner
+        } else if Role::Moderator.granted_by(roles) {
+            room_role.max(RoomRole::Moderator)
+        } else {
+            room_role
+        }
+    }
+
+    pub fn can_join_room(&self, room_name: &str, username: &str) -> bool {
+        !self.redis.private_rooms_set.room_private(room_name)
+            || self.room_role(room_name, username) == RoomRole::Owner
+            || self.redis.room_members_set.is_member(room_name, username)
+    }
+
+    pub fn readable_rooms(&self, username: &str) -> Vec<String> {
+        self.redis
+            .rooms_hash_map
+            .list_rooms()
+            .into_iter()
+            .filter(|room| {
+                self.can_join_room(room, username)
+                    && !self.redis.room_banned_users_set.user_banned(room, username)
+            })
+            .collect()
+    }
+
+    pub fn persist_room_members(&self, room_name: &str) -> ResponseFuture<Result<(), RoomError>> {
+        let room_name = room_name.to_owned();
+        let room_guid = match self.redis.rooms_hash_map.get_room_guid_by_name(&room_name) {
+            Some(val) => val,
+            None => return Box::pin(async move { Err(RoomError::NotFound(room_name)) }),
+        };
+        let fields = json!({
+            "members": self.redis.room_members_set.list_members(&room_name),
+        });
+
+        let elastic = self.elastic.clone();
+        Box::pin(async move {
+            elastic
+                .rooms
+                .update_room(&room_guid, fields)
+                .await
+                .map_err(|error| {
+                    log::error!(
+                        "Failed to persist members for room {} to elastic: {}",
+                        &room_name,
+                        error
+                    );
+                    RoomError::Failed
+                })
+        })
+    }
+
+    pub fn persist_room_moderation(
+        &self,
+        room_name: &str,
+    ) -> ResponseFuture<Result<(), RoomError>> {
+        let room_name = room_name.to_owned();
+        let room_guid = match self.redis.rooms_hash_map.get_room_guid_by_name(&room_name) {
+            Some(val) => val,
+            None => return Box::pin(async move { Err(RoomError::NotFound(room_name)) }),
+        };
+        let fields = json!({
+            "moderators": self.redis.room_moderators_set.list_moderators(&room_name),
+            "banned": self.redis.room_banned_users_set.list_banned_users(&room_name),
+            "muted": self.redis.room_muted_users_set.list_muted_users(&room_name),
+        });
+
+        let elastic = self.elastic.clone();
+        Box::pin(async move {
+            elastic
+                .rooms
+                .update_room(&room_guid, fields)
+                .await
+                .map_err(|error| {
+                    log::error!(
+                        "Failed to persist moderation for room {} to elastic: {}",
+                        &room_name,
+                        error
+                    );
+                    RoomError::Failed
+                })
+        })
+    }
 
-                if user_session.channel_name == channel_name {
-                    let _ = user_session.addr.do_send(Message {
-                        text: formatted_msg,
-                        color: self.select_color(msg_type),
+    pub fn publish_to_instances(&self, instances: HashSet<String>, message: &InstanceMessage) {
+        let payload = serde_json::to_string(message).unwrap();
+        for instance_id in instances {
+            self.redis
+                .publish_chat_messages
+                .publish_to_instance(&instance_id, &payload);
+        }
+    }
+
+    pub fn publish_chat_message(&self, chat_message: QueueMessage) {
+        let mut recipients: Vec<String> = match chat_message.chat_type {
+            ChatType::Room => self
+                .redis
+                .rooms_online_users_set
+                .list_users_in_room(&chat_message.recipient),
+            ChatType::Direct => chat_message
+                .recipient
+                .split(DIRECT_CHANNEL_SEPARATOR)
+                .map(|username| username.to_owned())
+                .collect(),
+            _ => vec![chat_message.recipient.clone()],
+        };
+        recipients.push(chat_message.sender.clone());
+
+        let instances: HashSet<String> = recipients
+            .iter()
+            .flat_map(|username| {
+                self.redis
+                    .session_instances_hash_map
+                    .user_instances(username)
+            })
+            .collect();
+        self.publish_to_instances(
+            instances,
+            &InstanceMessage::Chat {
+                message: chat_message,
+            },
+        );
+    }
+
+    pub fn forward_to_remote_sessions(&self, username: &str, message: InstanceMessage) {
+        let mut instances = self
+            .redis
+            .session_instances_hash_map
+            .user_instances(username);
+        instances.remove(&self.instance_id);
+        self.publish_to_instances(instances, &message);
+    }
+
+    pub fn send_local_notice(&self, username: &str, notice: &str) {
+        for user_session in self.user_sessions(username) {
+            user_session.addr.do_send(Message(ServerEvent::Notice {
+                text: notice.to_owned(),
+            }));
+        }
+    }
+
+    pub fn send_server_notice(&self, username: &str, notice: &str) {
+        self.send_local_notice(username, notice);
+        self.forward_to_remote_sessions(
+            username,
+            InstanceMessage::Notice {
+                username: username.to_owned(),
+                text: notice.to_owned(),
+            },
+        );
+    }
+
+    pub fn switch_local_sessions(
+        &mut self,
+        username: &str,
+        from_room: &str,
+        room: &str,
+        rejoin: bool,
+    ) {
+        let mut renamed_sessions = vec![];
+        if let Some(user_sessions) = self.sessions.get_mut(username) {
+            for user_session in user_sessions.values_mut() {
+                if user_session.chat_type == ChatType::Room
+                    && user_session.channel_name == from_room
+                {
+                    if !rejoin {
+                        user_session.channel_name = room.to_owned();
+                        renamed_sessions.push(user_session.connection_id.clone());
+                    }
+                    user_session.switch_room_addr.do_send(SwitchRoom {
+                        room: room.to_owned(),
+                        rejoin,
                     });
                 }
             }
         }
+        for connection_id in renamed_sessions {
+            self.refresh_presence(username, &connection_id);
+        }
     }
 
-    pub fn send_message_to_direct(
-        &self,
-        channel_name: &str,
-        sender: &str,
-        message: &str,
-        msg_type: &MessageType,
+    pub fn switch_room_sessions(
+        &mut self,
+        username: &str,
+        from_room: &str,
+        room: &str,
+        rejoin: bool,
     ) {
-        let mut recipient = String::from("");
-        let channel_users = channel_name.split("_").collect::<Vec<&str>>();
-        for user in channel_users {
-            if user != sender {
-                recipient = user.to_owned();
+        self.switch_local_sessions(username, from_room, room, rejoin);
+        self.forward_to_remote_sessions(
+            username,
+            InstanceMessage::SwitchRoom {
+                username: username.to_owned(),
+                from_room: from_room.to_owned(),
+                room: room.to_owned(),
+                rejoin,
+            },
+        );
+    }
+
+    pub fn close_local_sessions(&self, username: &str, reason: &str, except_connection_id: &str) {
+        for user_session in self.user_sessions(username) {
+            if user_session.connection_id != except_connection_id {
+                user_session.close_addr.do_send(CloseSession {
+                    reason: reason.to_owned(),
+                });
             }
         }
+    }
 
-        if let Some(recipient_session_state) = self.sessions.get(&recipient) {
-            let mut recipient_formatted_msg = String::from("");
+    pub fn close_sessions(&self, username: &str, reason: &str, except_connection_id: &str) {
+        self.close_local_sessions(username, reason, except_connection_id);
+        self.forward_to_remote_sessions(
+            username,
+            InstanceMessage::CloseSessions {
+                username: username.to_owned(),
+                reason: reason.to_owned(),
+                except_connection_id: except_connection_id.to_owned(),
+            },
+        );
+    }
 
-            match msg_type {
-                MessageType::Server => {
-                    if recipient_session_state.channel_name != channel_name
-                        && *sender != recipient_session_state.username
-                    {
-                        recipient_formatted_msg = format!("(direct) {}", &message);
-                    } else {
-                        recipient_formatted_msg = message.to_string();
-                    }
-                }
-                MessageType::Direct => {
-                    if recipient_session_state.channel_name != channel_name {
-                        recipient_formatted_msg = format!("(direct) {}: {}", &sender, &message);
-                    } else {
-                        recipient_formatted_msg = format!("{}: {}", &sender, &message);
-                    }
-                }
-                _ => {}
-            };
+    pub fn close_local_token_sessions(&self, username: &str, family: &str, reason: &str) {
+        for user_session in self.user_sessions(username) {
+            if user_session.family == family {
+                user_session.close_addr.do_send(CloseSession {
+                    reason: reason.to_owned(),
+                });
+            }
+        }
+    }
+
+    pub fn close_token_sessions(&self, username: &str, family: &str, reason: &str) {
+        self.close_local_token_sessions(username, family, reason);
+        self.forward_to_remote_sessions(
+            username,
+            InstanceMessage::CloseTokenSessions {
+                username: username.to_owned(),
+                family: family.to_owned(),
+                reason: reason.to_owned(),
+            },
+        );
+    }
 
-            let _ = recipient_session_state.addr.do_send(Message {
-                text: recipient_formatted_msg,
-                color: self.select_color(msg_type),
-            });
+    pub fn send_server_error(&self, username: &str, code: ErrorCode, message: &str) {
+        for user_session in self.user_sessions(username) {
+            user_session.addr.do_send(Message(ServerEvent::Error {
+                id: None,
+                code,
+                message: message.to_owned(),
+            }));
         }
+    }
 
-        if let Some(sender_state) = self.sessions.get(sender) {
-            let mut sender_formatted_msg = String::from("");
+    pub fn room_members(&self, room_name: &str) -> Vec<String> {
+        self.redis
+            .rooms_online_users_set
+            .list_users_in_room(room_name)
+    }
+
+    pub fn fetch_scrollback(
+        &self,
+        channel_name: &str,
+        chat_type: ChatType,
+        since: Option<u64>,
+        delivered_message_ids: BTreeSet<String>,
+    ) -> ResponseFuture<Vec<ServerEvent>> {
+        let elastic = self.elastic.clone();
+        let channel_name = channel_name.to_owned();
+        let scrollback_size = match since {
+            Some(_) => MAX_REPLAY_SIZE,
+            None => self.scrollback_size,
+        };
 
-            match msg_type {
-                MessageType::Server => {
-                    sender_formatted_msg = message.to_string();
+        Box::pin(async move {
+            match elastic
+                .messages
+                .recent_messages(&channel_name, &chat_type, scrollback_size, since)
+                .await
+            {
+                Ok(messages) => MessageDocument::history_events(messages, &delivered_message_ids),
+                Err(error) => {
+                    log::error!(
+                        "Failed to fetch scrollback for {}: {}",
+                        &channel_name,
+                        error
+                    );
+                    vec![]
                 }
-                MessageType::Direct => sender_formatted_msg = format!("{}: {}", &sender, &message),
-                _ => {}
-            };
-            let _ = sender_state.addr.do_send(Message {
-                text: sender_formatted_msg,
-                color: self.select_color(msg_type),
-            });
+            }
+        })
+    }
+
+    pub fn chat_event(msg: &SendClientMessage) -> ServerEvent {
+        match msg.presence {
+            Some(Presence::Joined) => ServerEvent::UserJoined {
+                username: msg.sender.clone(),
+                channel: msg.recipient.clone(),
+                chat_type: msg.chat_type.clone(),
+            },
+            Some(Presence::Left) => ServerEvent::UserLeft {
+                username: msg.sender.clone(),
+                channel: msg.recipient.clone(),
+                chat_type: msg.chat_type.clone(),
+            },
+            None if msg.msg_type == MessageType::Server => ServerEvent::Notice {
+                text: msg.msg.clone(),
+            },
+            None => ServerEvent::MessageReceived {
+                sender: msg.sender.clone(),
+                channel: msg.recipient.clone(),
+                chat_type: msg.chat_type.clone(),
+                msg_type: msg.msg_type.clone(),
+                text: msg.msg.clone(),
+                history: false,
+                queued: false,
+                message_id: msg.message_id.clone(),
+                created_at: msg.created_at,
+            },
         }
     }
 
-    pub fn whisper_message_to_recipient(&self, recipient: &str, sender: &str, message: &str) {
-        if let Some(recipient_session) = self.sessions.get(recipient) {
-            let _ = recipient_session.addr.do_send(Message {
-                text: format!("(whisper) {} {}", &sender, &message),
-                color: "pink".to_owned(),
-            });
+    pub fn send_message_to_room(&self, msg: &SendClientMessage) {
+        if let Some(channel_state) = self.rooms.get(&msg.recipient) {
+            for username in channel_state {
+                for user_session in self.user_sessions(username) {
+                    if user_session.chat_type == ChatType::Room
+                        && user_session.channel_name == msg.recipient
+                    {
+                        user_session.addr.do_send(Message(Self::chat_event(msg)));
+                    }
+                }
+            }
         }
-        if let Some(sender_session) = self.sessions.get(sender) {
-            let _ = sender_session.addr.do_send(Message {
-                text: format!("(whisper) {} {}", &sender, &message),
-                color: "pink".to_owned(),
-            });
+    }
+
+    pub fn send_message_to_direct(&self, msg: &SendClientMessage) {
+        for username in msg.recipient.split(DIRECT_CHANNEL_SEPARATOR) {
+            for user_session in self.user_sessions(username) {
+                user_session.addr.do_send(Message(Self::chat_event(msg)));
+            }
+        }
+    }
+
+    pub fn whisper_message_to_recipient(&self, msg: &SendClientMessage) {
+        for username in [&msg.recipient, &msg.sender] {
+            for user_session in self.user_sessions(username) {
+                user_session.addr.do_send(Message(Self::chat_event(msg)));
+            }
         }
     }
 }
 
-#[derive(Message, Serialize)]
+#[derive(Message)]
 #[rtype(result = "()")]
-pub struct Message {
-    pub text: String,
-    pub color: String,
-}
+pub struct Message(pub ServerEvent);
 
-#[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq)]
-pub enum ChatType {
-    Direct,
-    Room,
-    Whisper,
-    NoPreviousChatType,
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct SwitchRoom {
+    pub room: String,
+    pub rejoin: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq)]
-pub enum MessageType {
-    Direct,
-    Room,
-    Whisper,
-    Server,
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct CloseSession {
+    pub reason: String,
 }
 
 #[derive(Debug, Hash)]
 pub struct ChatSessionState {
     pub username: String,
+    pub connection_id: String,
     pub channel_name: String,
     pub addr: Recipient<Message>,
+    pub switch_room_addr: Recipient<SwitchRoom>,
+    pub close_addr: Recipient<CloseSession>,
+    pub family: String,
     pub chat_type: ChatType,
+    pub delivered_message_ids: BTreeSet<String>,
 }
 
 impl PartialEq for ChatSessionState {
     fn eq(&self, other: &Self) -> bool {
-        self.username == other.username
+        self.connection_id == other.connection_id
     }
 
     fn ne(&self, other: &Self) -> bool {
-        self.username != other.username
+        self.connection_id != other.connection_id
     }
 }
 
 impl Eq for ChatSessionState {}
-
-#[derive(Serialize, Deserialize)]
-pub struct QueueMessage {
-    pub sender: String,
-    pub chat_type: ChatType,
-    pub msg_type: MessageType,
-    pub recipient: String,
-    pub msg: String,
-}
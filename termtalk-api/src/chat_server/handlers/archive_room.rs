@@ -0,0 +1,65 @@
+use crate::chat_server::chat_server::ChatServer;
+use crate::constants::DEFAULT_ROOM;
+use crate::models::rooms::RoomError;
+use crate::utils::time_as_millis_since_epoch;
+use actix::prelude::*;
+use serde_json::json;
+use termtalk_proto::rooms::RoomRole;
+
+#[derive(Message)]
+#[rtype(result = "Result<(), RoomError>")]
+pub struct ArchiveRoom {
+    pub username: String,
+    pub room: String,
+}
+
+impl Handler<ArchiveRoom> for ChatServer {
+    type Result = ResponseFuture<Result<(), RoomError>>;
+
+    fn handle(&mut self, msg: ArchiveRoom, _: &mut Context<Self>) -> Self::Result {
+        if msg.room == DEFAULT_ROOM {
+            return Box::pin(async move { Err(RoomError::DefaultRoom) });
+        }
+        let room_guid = match self.redis.rooms_hash_map.get_room_guid_by_name(&msg.room) {
+            Some(val) => val,
+            None => return Box::pin(async move { Err(RoomError::NotFound(msg.room)) }),
+        };
+        if self.room_role(&msg.room, &msg.username) != RoomRole::Owner {
+            return Box::pin(async move { Err(RoomError::NotAllowed) });
+        }
+        if !self
+            .redis
+            .archived_rooms_set
+            .add_to_archived_rooms_set(&msg.room)
+        {
+            return Box::pin(async move { Err(RoomError::Archived(msg.room)) });
+        }
+
+        for username in self.room_members(&msg.room) {
+            if username != msg.username {
+                self.send_server_notice(
+                    &username,
+                    &format!(
+                        "Room {} was archived by {} and is now read only",
+                        &msg.room, &msg.username
+                    ),
+                );
+            }
+        }
+
+        let elastic = self.elastic.clone();
+        Box::pin(async move {
+            elastic
+                .rooms
+                .update_room(
+                    &room_guid,
+                    json!({ "archived_at": time_as_millis_since_epoch() }),
+                )
+                .await
+                .map_err(|error| {
+                    log::error!("Failed to archive room {} in elastic: {}", &msg.room, error);
+                    RoomError::Failed
+                })
+        })
+    }
+}
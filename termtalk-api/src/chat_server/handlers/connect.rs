@@ -1,37 +1,87 @@
-use crate::chat_server::chat_server::{ChatServer, ChatSessionState, ChatType, Message};
+use crate::chat_server::chat_server::{
+    ChatServer, ChatSessionState, CloseSession, Message, SwitchRoom,
+};
+use crate::models::sessions::SessionPolicy;
 use actix::prelude::*;
+use std::collections::BTreeSet;
+use termtalk_proto::chat::ChatType;
+use termtalk_proto::protocol::ServerEvent;
 
 #[derive(Message)]
 #[rtype(bool)]
 pub struct Connect {
     pub username: String,
+    pub connection_id: String,
     pub channel_name: String,
     pub addr: Recipient<Message>,
+    pub switch_room_addr: Recipient<SwitchRoom>,
+    pub close_addr: Recipient<CloseSession>,
     pub chat_type: ChatType,
+    pub family: String,
 }
 
 impl Handler<Connect> for ChatServer {
     type Result = bool;
 
     fn handle(&mut self, msg: Connect, _: &mut Context<Self>) -> Self::Result {
-        log::debug!("{} has connected to the server", msg.username);
-        let user_already_logged_in: bool = self.redis.users_online_set.user_online(&msg.username);
-        if user_already_logged_in {
-            return false;
+        log::debug!(
+            "{} has connected to the server with connection {}",
+            msg.username,
+            msg.connection_id
+        );
+        let user_already_logged_in: bool = self.user_online(&msg.username);
+        match self.session_policy {
+            SessionPolicy::RejectNew if user_already_logged_in => return false,
+            SessionPolicy::KickOld => self.close_sessions(
+                &msg.username,
+                "signed in from another client",
+                &msg.connection_id,
+            ),
+            _ => {}
         }
 
-        self.redis
-            .users_online_set
-            .add_to_users_online_set(&msg.username);
-        self.sessions.insert(
-            msg.username.clone(),
-            ChatSessionState {
-                username: msg.username.clone(),
-                channel_name: msg.channel_name.clone(),
-                chat_type: msg.chat_type.clone(),
-                addr: msg.addr,
-            },
-        );
+        let addr = msg.addr.clone();
+        self.sessions
+            .entry(msg.username.clone())
+            .or_default()
+            .insert(
+                msg.connection_id.clone(),
+                ChatSessionState {
+                    username: msg.username.clone(),
+                    connection_id: msg.connection_id.clone(),
+                    channel_name: msg.channel_name.clone(),
+                    chat_type: msg.chat_type.clone(),
+                    addr: msg.addr,
+                    switch_room_addr: msg.switch_room_addr,
+                    close_addr: msg.close_addr,
+                    family: msg.family,
+                    delivered_message_ids: BTreeSet::new(),
+                },
+            );
+        self.refresh_presence(&msg.username, &msg.connection_id);
+
+        let offline_messages = self
+            .redis
+            .offline_messages_list
+            .drain_messages(&msg.username);
+        if !offline_messages.is_empty() {
+            addr.do_send(Message(ServerEvent::Notice {
+                text: format!(
+                    "{} message{} while you were away",
+                    offline_messages.len(),
+                    if offline_messages.len() == 1 { "" } else { "s" }
+                ),
+            }));
+        }
+        if let Some(user_session) = self.user_session_mut(&msg.username, &msg.connection_id) {
+            user_session.delivered_message_ids = offline_messages
+                .iter()
+                .map(|message| message.message_id.clone())
+                .collect();
+        }
+        for message in offline_messages {
+            addr.do_send(Message(message.into_event(false)));
+        }
         true
     }
 }
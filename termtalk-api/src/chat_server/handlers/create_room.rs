@@ -0,0 +1,64 @@
+use crate::chat_server::chat_server::ChatServer;
+use crate::models::rooms::{is_valid_room_name, RoomError};
+use actix::prelude::*;
+use uuid::Uuid;
+
+#[derive(Message)]
+#[rtype(result = "Result<(), RoomError>")]
+pub struct CreateRoom {
+    pub username: String,
+    pub room: String,
+    pub private: bool,
+}
+
+impl Handler<CreateRoom> for ChatServer {
+    type Result = ResponseFuture<Result<(), RoomError>>;
+
+    fn handle(&mut self, msg: CreateRoom, _: &mut Context<Self>) -> Self::Result {
+        if !is_valid_room_name(&msg.room) {
+            return Box::pin(async move { Err(RoomError::InvalidName(msg.room)) });
+        }
+
+        let room_guid = Uuid::new_v4().to_string();
+        if !self
+            .redis
+            .rooms_hash_map
+            .set_room_guid_by_name_if_absent(&msg.room, &room_guid)
+        {
+            return Box::pin(async move { Err(RoomError::AlreadyExists(msg.room)) });
+        }
+        self.redis
+            .room_owners_hash_map
+            .set_room_owner(&msg.room, &msg.username);
+        if msg.private {
+            self.redis
+                .private_rooms_set
+                .add_to_private_rooms_set(&msg.room);
+            self.redis
+                .room_members_set
+                .add_member(&msg.room, &msg.username);
+        }
+
+        let redis = self.redis.clone();
+        let elastic = self.elastic.clone();
+        Box::pin(async move {
+            match elastic
+                .rooms
+                .create_room(&room_guid, &msg.room, &msg.username, msg.private)
+                .await
+            {
+                Ok(_) => Ok(()),
+                Err(error) => {
+                    log::error!("Failed to persist room {} to elastic: {}", &msg.room, error);
+                    redis.rooms_hash_map.remove_room(&msg.room);
+                    redis.room_owners_hash_map.remove_room_owner(&msg.room);
+                    redis
+                        .private_rooms_set
+                        .remove_from_private_rooms_set(&msg.room);
+                    redis.room_members_set.clear_room_set(&msg.room);
+                    Err(RoomError::Failed)
+                }
+            }
+        })
+    }
+}
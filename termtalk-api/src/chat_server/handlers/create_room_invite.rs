@@ -0,0 +1,41 @@
+use crate::chat_server::chat_server::ChatServer;
+use crate::constants::ROOM_INVITE_TTL_SECS;
+use crate::models::rooms::RoomError;
+use actix::prelude::*;
+use rand::{distributions::Alphanumeric, Rng};
+
+static ROOM_INVITE_CODE_LENGTH: usize = 10;
+
+#[derive(Message)]
+#[rtype(result = "Result<String, RoomError>")]
+pub struct CreateRoomInvite {
+    pub username: String,
+    pub room: String,
+}
+
+impl Handler<CreateRoomInvite> for ChatServer {
+    type Result = Result<String, RoomError>;
+
+    fn handle(&mut self, msg: CreateRoomInvite, _: &mut Context<Self>) -> Self::Result {
+        if !self.room_exists(&msg.room) {
+            return Err(RoomError::NotFound(msg.room));
+        }
+        if !self.redis.private_rooms_set.room_private(&msg.room) {
+            return Err(RoomError::NotPrivate(msg.room));
+        }
+        if !self.can_join_room(&msg.room, &msg.username) {
+            return Err(RoomError::Private(msg.room));
+        }
+
+        let code: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(ROOM_INVITE_CODE_LENGTH)
+            .map(char::from)
+            .collect();
+        self.redis
+            .room_invites
+            .create_invite(&code, &msg.room, ROOM_INVITE_TTL_SECS);
+
+        Ok(code)
+    }
+}
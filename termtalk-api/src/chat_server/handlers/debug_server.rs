@@ -10,6 +10,8 @@ impl Handler<DebugServer> for ChatServer {
     type Result = ();
 
     fn handle(&mut self, _msg: DebugServer, _ctx: &mut Context<Self>) -> Self::Result {
+        log::info!("self.instance_id: {}\n", self.instance_id);
+        log::info!("self.sessions: {:?}\n", self.sessions);
         log::info!("self.directs: {:?}\n", self.directs);
         log::info!("self.rooms: {:?}\n", self.rooms);
     }
@@ -0,0 +1,71 @@
+use crate::chat_server::chat_server::ChatServer;
+use crate::constants::DEFAULT_ROOM;
+use crate::models::rooms::RoomError;
+use crate::utils::time_as_millis_since_epoch;
+use actix::prelude::*;
+use serde_json::json;
+use termtalk_proto::rooms::RoomRole;
+
+#[derive(Message)]
+#[rtype(result = "Result<(), RoomError>")]
+pub struct DeleteRoom {
+    pub username: String,
+    pub room: String,
+}
+
+impl Handler<DeleteRoom> for ChatServer {
+    type Result = ResponseFuture<Result<(), RoomError>>;
+
+    fn handle(&mut self, msg: DeleteRoom, _: &mut Context<Self>) -> Self::Result {
+        if msg.room == DEFAULT_ROOM {
+            return Box::pin(async move { Err(RoomError::DefaultRoom) });
+        }
+        let room_guid = match self.redis.rooms_hash_map.get_room_guid_by_name(&msg.room) {
+            Some(val) => val,
+            None => return Box::pin(async move { Err(RoomError::NotFound(msg.room)) }),
+        };
+        if self.room_role(&msg.room, &msg.username) != RoomRole::Owner {
+            return Box::pin(async move { Err(RoomError::NotAllowed) });
+        }
+        self.redis.rooms_hash_map.remove_room(&msg.room);
+        self.redis
+            .archived_rooms_set
+            .remove_from_archived_rooms_set(&msg.room);
+        self.redis.room_owners_hash_map.remove_room_owner(&msg.room);
+        self.redis.room_moderators_set.clear_room_set(&msg.room);
+        self.redis.room_banned_users_set.clear_room_set(&msg.room);
+        self.redis.room_muted_users_set.clear_room_set(&msg.room);
+        self.redis
+            .private_rooms_set
+            .remove_from_private_rooms_set(&msg.room);
+        self.redis.room_members_set.clear_room_set(&msg.room);
+
+        for username in self.room_members(&msg.room) {
+            if username != msg.username {
+                self.send_server_notice(
+                    &username,
+                    &format!(
+                        "Room {} was deleted by {}. Moving you to {}",
+                        &msg.room, &msg.username, DEFAULT_ROOM
+                    ),
+                );
+            }
+            self.switch_room_sessions(&username, &msg.room, DEFAULT_ROOM, true);
+        }
+
+        let elastic = self.elastic.clone();
+        Box::pin(async move {
+            elastic
+                .rooms
+                .update_room(
+                    &room_guid,
+                    json!({ "deleted_at": time_as_millis_since_epoch() }),
+                )
+                .await
+                .map_err(|error| {
+                    log::error!("Failed to delete room {} in elastic: {}", &msg.room, error);
+                    RoomError::Failed
+                })
+        })
+    }
+}
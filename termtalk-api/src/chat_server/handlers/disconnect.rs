@@ -1,10 +1,12 @@
-use crate::chat_server::chat_server::{ChatServer, ChatType, MessageType, QueueMessage};
+use crate::chat_server::chat_server::ChatServer;
 use actix::prelude::*;
+use termtalk_proto::chat::{ChatType, MessageType, Presence, QueueMessage};
 
 #[derive(Message)]
 #[rtype(result = "()")]
 pub struct Disconnect {
     pub username: String,
+    pub connection_id: String,
 }
 
 impl Handler<Disconnect> for ChatServer {
@@ -12,12 +14,39 @@ impl Handler<Disconnect> for ChatServer {
 
     fn handle(&mut self, msg: Disconnect, _: &mut Context<Self>) {
         self.redis
-            .users_online_set
-            .remove_from_users_online_set(&msg.username);
+            .presence_leases
+            .remove_lease(&msg.username, &msg.connection_id);
+        self.redis
+            .session_instances_hash_map
+            .remove_session_instance(&msg.username, &msg.connection_id);
+        let removed_session = self
+            .sessions
+            .get_mut(&msg.username)
+            .and_then(|user_sessions| user_sessions.remove(&msg.connection_id));
+        let still_online = self.user_sessions(&msg.username).next().is_some();
+        if !still_online {
+            self.sessions.remove(&msg.username);
+        }
+        if !self.user_online(&msg.username) {
+            self.redis
+                .users_online_set
+                .remove_from_users_online_set(&msg.username);
+        }
 
-        let removed_session = self.sessions.remove(&msg.username);
-        if removed_session.is_some() {
-            let inner_removed_session = removed_session.unwrap();
+        if let Some(inner_removed_session) = removed_session {
+            if self.user_in_channel(
+                &msg.username,
+                &inner_removed_session.channel_name,
+                &inner_removed_session.chat_type,
+            ) {
+                return;
+            }
+            let in_channel_elsewhere = self.user_in_channel_anywhere(
+                &msg.username,
+                &inner_removed_session.channel_name,
+                &inner_removed_session.chat_type,
+                &msg.connection_id,
+            );
             match inner_removed_session.chat_type {
                 ChatType::Direct => {
                     if let Some(direct_state) =
@@ -32,6 +61,9 @@ impl Handler<Disconnect> for ChatServer {
                     {
                         room_state.remove(&inner_removed_session.username);
                     }
+                    if in_channel_elsewhere {
+                        return;
+                    }
                     self.redis.rooms_online_users_set.remove_user_from_room_set(
                         &inner_removed_session.channel_name,
                         &inner_removed_session.username,
@@ -39,17 +71,24 @@ impl Handler<Disconnect> for ChatServer {
 
                     let chat_message = QueueMessage {
                         sender: msg.username.clone(),
-                        msg: format!(
-                            "User {} disconnected from {} and is now offline",
-                            &msg.username, &inner_removed_session.channel_name
-                        ),
+                        msg: match still_online {
+                            true => format!(
+                                "User {} disconnected from room {}",
+                                &msg.username, &inner_removed_session.channel_name
+                            ),
+                            false => format!(
+                                "User {} disconnected from {} and is now offline",
+                                &msg.username, &inner_removed_session.channel_name
+                            ),
+                        },
                         chat_type: ChatType::Room,
                         msg_type: MessageType::Server,
                         recipient: inner_removed_session.channel_name.clone(),
+                        presence: Some(Presence::Left),
+                        message_id: None,
+                        created_at: None,
                     };
-                    self.redis
-                        .publish_chat_messages
-                        .publish_to_channel(chat_message);
+                    self.publish_chat_message(chat_message);
                 }
                 _ => {}
             };
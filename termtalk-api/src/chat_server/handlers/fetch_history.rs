@@ -0,0 +1,26 @@
+use crate::chat_server::chat_server::ChatServer;
+use actix::prelude::*;
+use std::collections::BTreeSet;
+use termtalk_proto::chat::ChatType;
+use termtalk_proto::protocol::ServerEvent;
+
+#[derive(Message)]
+#[rtype(result = "Vec<ServerEvent>")]
+pub struct FetchHistory {
+    pub channel_name: String,
+    pub chat_type: ChatType,
+    pub since: u64,
+}
+
+impl Handler<FetchHistory> for ChatServer {
+    type Result = ResponseFuture<Vec<ServerEvent>>;
+
+    fn handle(&mut self, msg: FetchHistory, _: &mut Context<Self>) -> Self::Result {
+        self.fetch_scrollback(
+            &msg.channel_name,
+            msg.chat_type,
+            Some(msg.since),
+            BTreeSet::new(),
+        )
+    }
+}
@@ -0,0 +1,116 @@
+use crate::chat_server::chat_server::ChatServer;
+use crate::chat_server::handlers::send_client_message::SendClientMessage;
+use actix::prelude::*;
+use serde::{Deserialize, Serialize};
+use termtalk_proto::chat::QueueMessage;
+
+#[derive(Message, Serialize, Deserialize)]
+#[rtype(result = "()")]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InstanceMessage {
+    Chat {
+        message: QueueMessage,
+    },
+    Notice {
+        username: String,
+        text: String,
+    },
+    SwitchRoom {
+        username: String,
+        from_room: String,
+        room: String,
+        rejoin: bool,
+    },
+    CloseSessions {
+        username: String,
+        reason: String,
+        except_connection_id: String,
+    },
+    CloseTokenSessions {
+        username: String,
+        family: String,
+        reason: String,
+    },
+}
+
+impl Handler<InstanceMessage> for ChatServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: InstanceMessage, ctx: &mut Context<Self>) {
+        match msg {
+            InstanceMessage::Chat { message } => Handler::<SendClientMessage>::handle(
+                self,
+                SendClientMessage {
+                    sender: message.sender,
+                    msg: message.msg,
+                    chat_type: message.chat_type,
+                    msg_type: message.msg_type,
+                    recipient: message.recipient,
+                    presence: message.presence,
+                    message_id: message.message_id,
+                    created_at: message.created_at,
+                },
+                ctx,
+            ),
+            InstanceMessage::Notice { username, text } => self.send_local_notice(&username, &text),
+            InstanceMessage::SwitchRoom {
+                username,
+                from_room,
+                room,
+                rejoin,
+            } => self.switch_local_sessions(&username, &from_room, &room, rejoin),
+            InstanceMessage::CloseSessions {
+                username,
+                reason,
+                except_connection_id,
+            } => self.close_local_sessions(&username, &reason, &except_connection_id),
+            InstanceMessage::CloseTokenSessions {
+                username,
+                family,
+                reason,
+            } => self.close_local_token_sessions(&username, &family, &reason),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use termtalk_proto::chat::{ChatType, MessageType};
+
+    #[test]
+    fn test_instance_message_wire_format() {
+        let payload = serde_json::to_string(&InstanceMessage::Chat {
+            message: QueueMessage {
+                sender: "alice".to_owned(),
+                chat_type: ChatType::Room,
+                msg_type: MessageType::Room,
+                recipient: "Main".to_owned(),
+                msg: "hi".to_owned(),
+                presence: None,
+                message_id: Some("m1".to_owned()),
+                created_at: Some(1718000000123),
+            },
+        })
+        .unwrap();
+        assert!(payload.starts_with(r#"{"type":"chat","message":{"sender":"alice""#));
+
+        let switch_room: InstanceMessage = serde_json::from_str(
+            r#"{"type":"switch_room","username":"bob","from_room":"Ops","room":"Main","rejoin":true}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            switch_room,
+            InstanceMessage::SwitchRoom { rejoin: true, .. }
+        ));
+
+        let close_token_sessions: InstanceMessage = serde_json::from_str(
+            r#"{"type":"close_token_sessions","username":"bob","family":"abc","reason":"signed out"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            close_token_sessions,
+            InstanceMessage::CloseTokenSessions { family, .. } if family == "abc"
+        ));
+    }
+}
@@ -12,7 +12,6 @@ impl Handler<IsUserOnline> for ChatServer {
     type Result = bool;
 
     fn handle(&mut self, msg: IsUserOnline, _ctx: &mut Context<Self>) -> bool {
-        let redis = self.redis.clone();
-        redis.users_online_set.user_online(&msg.username)
+        self.user_online(&msg.username)
     }
 }
@@ -1,41 +1,75 @@
-use crate::chat_server::chat_server::{ChatServer, ChatType, MessageType, QueueMessage};
+use crate::chat_server::chat_server::ChatServer;
+use crate::models::messages::MessageDocument;
 use actix::prelude::*;
 use std::collections::HashSet;
+use termtalk_proto::chat::{ChatType, MessageType, Presence, QueueMessage};
+use termtalk_proto::protocol::ServerEvent;
 
 #[derive(Message)]
-#[rtype(result = "()")]
+#[rtype(result = "Vec<ServerEvent>")]
 pub struct JoinDirect {
     pub sender: String,
+    pub connection_id: String,
     pub recipient: String,
     pub channel_name: String,
     pub previous_channel_name: String,
     pub previous_chat_type: ChatType,
+    pub since: Option<u64>,
 }
 
 impl Handler<JoinDirect> for ChatServer {
-    type Result = ();
+    type Result = ResponseFuture<Vec<ServerEvent>>;
 
-    fn handle(&mut self, msg: JoinDirect, _: &mut Context<Self>) {
-        match msg.previous_chat_type {
+    fn handle(&mut self, msg: JoinDirect, _: &mut Context<Self>) -> Self::Result {
+        if let Some(user_session) = self.user_session_mut(&msg.sender, &msg.connection_id) {
+            user_session.channel_name = msg.channel_name.clone();
+            user_session.chat_type = ChatType::Direct;
+        }
+        self.refresh_presence(&msg.sender, &msg.connection_id);
+
+        let previous_chat_type = match self.user_in_channel(
+            &msg.sender,
+            &msg.previous_channel_name,
+            &msg.previous_chat_type,
+        ) {
+            true => ChatType::NoPreviousChatType,
+            false => msg.previous_chat_type.clone(),
+        };
+        let in_previous_channel_elsewhere = self.user_in_channel_anywhere(
+            &msg.sender,
+            &msg.previous_channel_name,
+            &msg.previous_chat_type,
+            &msg.connection_id,
+        );
+        match previous_chat_type {
             ChatType::Direct => {
                 if let Some(direct_state) = self.directs.get_mut(&msg.previous_channel_name) {
                     direct_state.remove(&msg.sender);
+                }
+                if self.directs.contains_key(&msg.previous_channel_name)
+                    && !in_previous_channel_elsewhere
+                {
                     let chat_message = QueueMessage {
                         sender: msg.sender.clone(),
                         msg: format!("User {} left direct chat", &msg.sender),
                         chat_type: ChatType::Direct,
                         msg_type: MessageType::Server,
                         recipient: msg.previous_channel_name.clone(),
+                        presence: Some(Presence::Left),
+                        message_id: None,
+                        created_at: None,
                     };
 
-                    self.redis
-                        .publish_chat_messages
-                        .publish_to_channel(chat_message);
+                    self.publish_chat_message(chat_message);
                 }
             }
             ChatType::Room => {
                 if let Some(room_state) = self.rooms.get_mut(&msg.previous_channel_name) {
                     room_state.remove(&msg.sender);
+                }
+                if self.rooms.contains_key(&msg.previous_channel_name)
+                    && !in_previous_channel_elsewhere
+                {
                     self.redis
                         .rooms_online_users_set
                         .remove_user_from_room_set(&msg.previous_channel_name, &msg.sender);
@@ -47,17 +81,28 @@ impl Handler<JoinDirect> for ChatServer {
                         ),
                         chat_type: ChatType::Room,
                         msg_type: MessageType::Server,
-                        recipient: msg.channel_name.clone(),
+                        recipient: msg.previous_channel_name.clone(),
+                        presence: Some(Presence::Left),
+                        message_id: None,
+                        created_at: None,
                     };
 
-                    self.redis
-                        .publish_chat_messages
-                        .publish_to_channel(chat_message);
+                    self.publish_chat_message(chat_message);
                 }
             }
             _ => {}
         };
 
+        let already_joined = self
+            .directs
+            .get(&msg.channel_name)
+            .is_some_and(|direct_state| direct_state.contains(&msg.sender))
+            || self.user_in_channel_anywhere(
+                &msg.sender,
+                &msg.channel_name,
+                &ChatType::Direct,
+                &msg.connection_id,
+            );
         let mut new_channel_set = HashSet::new();
         new_channel_set.insert(msg.sender.clone());
 
@@ -68,19 +113,54 @@ impl Handler<JoinDirect> for ChatServer {
             })
             .or_insert(new_channel_set);
 
-        let chat_message = QueueMessage {
-            sender: msg.sender.clone(),
-            msg: format!(
-                "User {} is direct chatting with {}",
-                &msg.sender, &msg.recipient
-            ),
-            chat_type: ChatType::Direct,
-            msg_type: MessageType::Server,
-            recipient: msg.channel_name.clone(),
-        };
+        if !already_joined {
+            let chat_message = QueueMessage {
+                sender: msg.sender.clone(),
+                msg: format!(
+                    "User {} is direct chatting with {}",
+                    &msg.sender, &msg.recipient
+                ),
+                chat_type: ChatType::Direct,
+                msg_type: MessageType::Server,
+                recipient: msg.channel_name.clone(),
+                presence: Some(Presence::Joined),
+                message_id: None,
+                created_at: None,
+            };
+
+            self.publish_chat_message(chat_message);
+        }
 
         self.redis
-            .publish_chat_messages
-            .publish_to_channel(chat_message);
+            .direct_unread_hash_map
+            .clear_unread(&msg.sender, &msg.channel_name);
+        let delivered_message_ids = self
+            .user_session_mut(&msg.sender, &msg.connection_id)
+            .map(|user_session| user_session.delivered_message_ids.clone())
+            .unwrap_or_default();
+        let scrollback = self.fetch_scrollback(
+            &msg.channel_name,
+            ChatType::Direct,
+            msg.since,
+            delivered_message_ids,
+        );
+        let elastic = self.elastic.clone();
+        let participants =
+            MessageDocument::participants_for(&msg.sender, &msg.channel_name, &ChatType::Direct);
+        actix::spawn(async move {
+            if let Err(error) = elastic
+                .direct_chats
+                .open_direct_chat(&msg.channel_name, &participants)
+                .await
+            {
+                log::error!(
+                    "Failed to persist direct chat {} to elastic: {}",
+                    &msg.channel_name,
+                    error
+                );
+            }
+        });
+
+        scrollback
     }
 }
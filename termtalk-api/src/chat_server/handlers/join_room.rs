@@ -1,64 +1,126 @@
-use crate::chat_server::chat_server::{ChatServer, ChatType, MessageType, QueueMessage};
+use crate::chat_server::chat_server::ChatServer;
+use crate::models::rooms::RoomError;
 use actix::prelude::*;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
+use termtalk_proto::chat::{ChatType, MessageType, Presence, QueueMessage};
+use termtalk_proto::protocol::ServerEvent;
 
 #[derive(Message)]
-#[rtype(result = "()")]
+#[rtype(result = "Result<Vec<ServerEvent>, RoomError>")]
 pub struct JoinRoom {
     pub username: String,
+    pub connection_id: String,
     pub channel_name: String,
     pub chat_type: ChatType,
     pub previous_channel_name: String,
     pub previous_chat_type: ChatType,
+    pub invite: Option<String>,
+    pub since: Option<u64>,
 }
 
 impl Handler<JoinRoom> for ChatServer {
-    type Result = ();
+    type Result = ResponseFuture<Result<Vec<ServerEvent>, RoomError>>;
 
-    fn handle(&mut self, msg: JoinRoom, _: &mut Context<Self>) {
-        match msg.previous_chat_type {
+    fn handle(&mut self, msg: JoinRoom, _: &mut Context<Self>) -> Self::Result {
+        if !self.room_exists(&msg.channel_name) {
+            return Box::pin(async move { Err(RoomError::NotFound(msg.channel_name)) });
+        }
+        if self
+            .redis
+            .room_banned_users_set
+            .user_banned(&msg.channel_name, &msg.username)
+        {
+            return Box::pin(async move { Err(RoomError::Banned(msg.channel_name)) });
+        }
+        let mut persist_members = None;
+        if !self.can_join_room(&msg.channel_name, &msg.username) {
+            let redeemed = match &msg.invite {
+                Some(code) => self
+                    .redis
+                    .room_invites
+                    .redeem_invite(code, &msg.channel_name),
+                None => return Box::pin(async move { Err(RoomError::Private(msg.channel_name)) }),
+            };
+            if !redeemed {
+                return Box::pin(async move { Err(RoomError::InvalidInvite(msg.channel_name)) });
+            }
+            self.redis
+                .room_members_set
+                .add_member(&msg.channel_name, &msg.username);
+            persist_members = Some(self.persist_room_members(&msg.channel_name));
+        }
+        if let Some(user_session) = self.user_session_mut(&msg.username, &msg.connection_id) {
+            user_session.channel_name = msg.channel_name.clone();
+            user_session.chat_type = ChatType::Room;
+        }
+
+        let previous_chat_type = match self.user_in_channel(
+            &msg.username,
+            &msg.previous_channel_name,
+            &msg.previous_chat_type,
+        ) {
+            true => ChatType::NoPreviousChatType,
+            false => msg.previous_chat_type.clone(),
+        };
+        let in_previous_channel_elsewhere = self.user_in_channel_anywhere(
+            &msg.username,
+            &msg.previous_channel_name,
+            &msg.previous_chat_type,
+            &msg.connection_id,
+        );
+        match previous_chat_type {
             ChatType::Direct => {
                 if let Some(direct_state) = self.directs.get_mut(&msg.previous_channel_name) {
                     direct_state.remove(&msg.username);
                 }
-                let chat_message = QueueMessage {
-                    sender: msg.username.clone(),
-                    msg: format!("User {} left direct chat", &msg.username),
-                    chat_type: ChatType::Direct,
-                    msg_type: MessageType::Server,
-                    recipient: msg.previous_channel_name.clone(),
-                };
+                if !in_previous_channel_elsewhere {
+                    let chat_message = QueueMessage {
+                        sender: msg.username.clone(),
+                        msg: format!("User {} left direct chat", &msg.username),
+                        chat_type: ChatType::Direct,
+                        msg_type: MessageType::Server,
+                        recipient: msg.previous_channel_name.clone(),
+                        presence: Some(Presence::Left),
+                        message_id: None,
+                        created_at: None,
+                    };
 
-                self.redis
-                    .publish_chat_messages
-                    .publish_to_channel(chat_message);
+                    self.publish_chat_message(chat_message);
+                }
             }
             ChatType::Room => {
                 if let Some(room_state) = self.rooms.get_mut(&msg.previous_channel_name) {
                     room_state.remove(&msg.username);
                 }
-                self.redis
-                    .rooms_online_users_set
-                    .remove_user_from_room_set(&msg.channel_name, &msg.username);
+                if !in_previous_channel_elsewhere {
+                    self.redis
+                        .rooms_online_users_set
+                        .remove_user_from_room_set(&msg.previous_channel_name, &msg.username);
 
-                let chat_message = QueueMessage {
-                    sender: msg.username.clone(),
-                    msg: format!(
-                        "User {} disconnected from room {}",
-                        &msg.username, &msg.previous_channel_name
-                    ),
-                    chat_type: ChatType::Room,
-                    msg_type: MessageType::Server,
-                    recipient: msg.previous_channel_name.clone(),
-                };
+                    let chat_message = QueueMessage {
+                        sender: msg.username.clone(),
+                        msg: format!(
+                            "User {} disconnected from room {}",
+                            &msg.username, &msg.previous_channel_name
+                        ),
+                        chat_type: ChatType::Room,
+                        msg_type: MessageType::Server,
+                        recipient: msg.previous_channel_name.clone(),
+                        presence: Some(Presence::Left),
+                        message_id: None,
+                        created_at: None,
+                    };
 
-                self.redis
-                    .publish_chat_messages
-                    .publish_to_channel(chat_message);
+                    self.publish_chat_message(chat_message);
+                }
             }
             _ => {}
         };
 
+        let already_joined = self
+            .redis
+            .rooms_online_users_set
+            .user_in_room(&msg.channel_name, &msg.username);
         let mut new_channel_set = HashSet::new();
         new_channel_set.insert(msg.username.clone());
 
@@ -69,22 +131,39 @@ impl Handler<JoinRoom> for ChatServer {
             })
             .or_insert(new_channel_set);
 
-        let chat_message = QueueMessage {
-            sender: msg.username.clone(),
-            msg: format!(
-                "User {} connected to room {}",
-                &msg.username, &msg.channel_name
-            ),
-            chat_type: ChatType::Room,
-            msg_type: MessageType::Server,
-            recipient: msg.channel_name.clone(),
-        };
+        if !already_joined {
+            let chat_message = QueueMessage {
+                sender: msg.username.clone(),
+                msg: format!(
+                    "User {} connected to room {}",
+                    &msg.username, &msg.channel_name
+                ),
+                chat_type: ChatType::Room,
+                msg_type: MessageType::Server,
+                recipient: msg.channel_name.clone(),
+                presence: Some(Presence::Joined),
+                message_id: None,
+                created_at: None,
+            };
 
-        self.redis
-            .publish_chat_messages
-            .publish_to_channel(chat_message);
+            self.publish_chat_message(chat_message);
+        }
         self.redis
             .rooms_online_users_set
             .add_user_to_room_set(&msg.channel_name, &msg.username);
+        self.refresh_presence(&msg.username, &msg.connection_id);
+
+        let scrollback = self.fetch_scrollback(
+            &msg.channel_name,
+            ChatType::Room,
+            msg.since,
+            BTreeSet::new(),
+        );
+        Box::pin(async move {
+            if let Some(persist_members) = persist_members {
+                let _ = persist_members.await;
+            }
+            Ok(scrollback.await)
+        })
     }
 }
@@ -0,0 +1,42 @@
+use crate::chat_server::chat_server::ChatServer;
+use crate::constants::DEFAULT_DIRECT_CHATS_SIZE;
+use actix::prelude::*;
+use termtalk_proto::direct_chats::DirectChatSummary;
+
+pub struct ListDirectChats {
+    pub username: String,
+}
+
+impl actix::Message for ListDirectChats {
+    type Result = Result<Vec<DirectChatSummary>, String>;
+}
+
+impl Handler<ListDirectChats> for ChatServer {
+    type Result = ResponseFuture<Result<Vec<DirectChatSummary>, String>>;
+
+    fn handle(&mut self, msg: ListDirectChats, _: &mut Context<Self>) -> Self::Result {
+        let elastic = self.elastic.clone();
+        let redis = self.redis.clone();
+
+        Box::pin(async move {
+            let direct_chats = elastic
+                .direct_chats
+                .recent_direct_chats(&msg.username, DEFAULT_DIRECT_CHATS_SIZE)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to list direct chats for {}: {}", &msg.username, e);
+                    String::from("Something went wrong while listing direct chats")
+                })?;
+
+            Ok(direct_chats
+                .into_iter()
+                .map(|direct_chat| {
+                    let unread = redis
+                        .direct_unread_hash_map
+                        .get_unread(&msg.username, &direct_chat.name);
+                    direct_chat.into_summary(&msg.username, unread)
+                })
+                .collect())
+        })
+    }
+}
@@ -1,17 +1,32 @@
 use crate::chat_server::chat_server::ChatServer;
 use actix::prelude::*;
+use termtalk_proto::rooms::RoomSummary;
 
-pub struct ListRooms;
+pub struct ListRooms {
+    pub username: String,
+}
 
 impl actix::Message for ListRooms {
-    type Result = Vec<String>;
+    type Result = Vec<RoomSummary>;
 }
 
 impl Handler<ListRooms> for ChatServer {
     type Result = MessageResult<ListRooms>;
 
-    fn handle(&mut self, _: ListRooms, _: &mut Context<Self>) -> Self::Result {
-        let list_rooms: Vec<String> = self.redis.rooms_hash_map.list_rooms();
+    fn handle(&mut self, msg: ListRooms, _: &mut Context<Self>) -> Self::Result {
+        let mut room_names: Vec<String> = self.redis.rooms_hash_map.list_rooms();
+        room_names.sort();
+
+        let list_rooms: Vec<RoomSummary> = room_names
+            .into_iter()
+            .filter(|name| self.can_join_room(name, &msg.username))
+            .map(|name| RoomSummary {
+                online_users: self.redis.rooms_online_users_set.count_users_in_room(&name),
+                archived: self.redis.archived_rooms_set.room_archived(&name),
+                private: self.redis.private_rooms_set.room_private(&name),
+                name,
+            })
+            .collect();
 
         MessageResult(list_rooms)
     }
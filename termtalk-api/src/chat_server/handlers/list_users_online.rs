@@ -11,7 +11,13 @@ impl Handler<ListUsersOnline> for ChatServer {
     type Result = MessageResult<ListUsersOnline>;
 
     fn handle(&mut self, _: ListUsersOnline, _: &mut Context<Self>) -> Self::Result {
-        let users_online = self.redis.users_online_set.users_online();
+        let users_online = self
+            .redis
+            .users_online_set
+            .users_online()
+            .into_iter()
+            .filter(|username| self.user_online(username))
+            .collect();
         MessageResult(users_online)
     }
 }
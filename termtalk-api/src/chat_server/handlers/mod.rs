@@ -1,12 +1,28 @@
+pub mod archive_room;
 pub mod connect;
+pub mod create_room;
+pub mod create_room_invite;
 pub mod debug_server;
+pub mod delete_room;
 pub mod disconnect;
+pub mod fetch_history;
+pub mod instance_message;
 pub mod is_user_online;
 pub mod join_direct;
 pub mod join_room;
+pub mod list_direct_chats;
 pub mod list_rooms;
 pub mod list_users_in_room;
 pub mod list_users_online;
+pub mod moderate_room;
+pub mod readable_rooms;
+pub mod refresh_presence;
+pub mod rename_room;
+pub mod revoke_token;
+pub mod revoke_user_tokens;
+pub mod search_messages;
 pub mod send_client_message;
 pub mod session_message;
+pub mod set_room_role;
 pub mod update_session_status;
+pub mod user_exists;
@@ -0,0 +1,110 @@
+use crate::chat_server::chat_server::ChatServer;
+use crate::constants::DEFAULT_ROOM;
+use crate::models::roles::Role;
+use crate::models::rooms::RoomError;
+use actix::prelude::*;
+use termtalk_proto::chat::{ChatType, MessageType, QueueMessage};
+use termtalk_proto::rooms::ModerationAction;
+
+#[derive(Message)]
+#[rtype(result = "Result<(), RoomError>")]
+pub struct ModerateRoom {
+    pub username: String,
+    pub room: String,
+    pub target: String,
+    pub action: ModerationAction,
+    pub roles: Vec<Role>,
+}
+
+impl Handler<ModerateRoom> for ChatServer {
+    type Result = ResponseFuture<Result<(), RoomError>>;
+
+    fn handle(&mut self, msg: ModerateRoom, _: &mut Context<Self>) -> Self::Result {
+        if msg.target == msg.username {
+            return Box::pin(async move { Err(RoomError::InvalidTarget) });
+        }
+        if !self.room_exists(&msg.room) {
+            return Box::pin(async move { Err(RoomError::NotFound(msg.room)) });
+        }
+        let actor_role = self.moderator_room_role(&msg.room, &msg.username, &msg.roles);
+        let target_role = self.room_role(&msg.room, &msg.target);
+        if !actor_role.can_moderate(&target_role) {
+            return Box::pin(async move { Err(RoomError::NotAllowed) });
+        }
+
+        let target_in_room = self.room_members(&msg.room).contains(&msg.target);
+        match msg.action {
+            ModerationAction::Kick => {
+                if !target_in_room {
+                    return Box::pin(async move { Err(RoomError::NotInRoom(msg.target)) });
+                }
+            }
+            ModerationAction::Ban => {
+                self.redis
+                    .room_banned_users_set
+                    .add_banned_user(&msg.room, &msg.target);
+            }
+            ModerationAction::Unban => {
+                self.redis
+                    .room_banned_users_set
+                    .remove_banned_user(&msg.room, &msg.target);
+            }
+            ModerationAction::Mute => {
+                self.redis
+                    .room_muted_users_set
+                    .add_muted_user(&msg.room, &msg.target);
+            }
+            ModerationAction::Unmute => {
+                self.redis
+                    .room_muted_users_set
+                    .remove_muted_user(&msg.room, &msg.target);
+            }
+        };
+
+        let preposition = match msg.action {
+            ModerationAction::Mute | ModerationAction::Unmute => "in",
+            _ => "from",
+        };
+        let chat_message = QueueMessage {
+            sender: msg.username.clone(),
+            msg: format!(
+                "User {} was {} {} room {} by {}",
+                &msg.target,
+                msg.action.past_tense(),
+                preposition,
+                &msg.room,
+                &msg.username
+            ),
+            chat_type: ChatType::Room,
+            msg_type: MessageType::Server,
+            recipient: msg.room.clone(),
+            presence: None,
+            message_id: None,
+            created_at: None,
+        };
+        self.publish_chat_message(chat_message);
+
+        let removes_target = matches!(msg.action, ModerationAction::Kick | ModerationAction::Ban);
+        if removes_target || !target_in_room {
+            self.send_server_notice(
+                &msg.target,
+                &format!(
+                    "You were {} {} room {} by {}",
+                    msg.action.past_tense(),
+                    preposition,
+                    &msg.room,
+                    &msg.username
+                ),
+            );
+        }
+
+        if removes_target && target_in_room {
+            self.switch_room_sessions(&msg.target, &msg.room, DEFAULT_ROOM, true);
+        }
+
+        if msg.action == ModerationAction::Kick {
+            return Box::pin(async move { Ok(()) });
+        }
+        self.persist_room_moderation(&msg.room)
+    }
+}
@@ -0,0 +1,18 @@
+use crate::chat_server::chat_server::ChatServer;
+use actix::prelude::*;
+
+pub struct ReadableRooms {
+    pub username: String,
+}
+
+impl actix::Message for ReadableRooms {
+    type Result = Vec<String>;
+}
+
+impl Handler<ReadableRooms> for ChatServer {
+    type Result = MessageResult<ReadableRooms>;
+
+    fn handle(&mut self, msg: ReadableRooms, _: &mut Context<Self>) -> Self::Result {
+        MessageResult(self.readable_rooms(&msg.username))
+    }
+}
@@ -0,0 +1,17 @@
+use crate::chat_server::chat_server::ChatServer;
+use actix::prelude::*;
+
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct RefreshPresence {
+    pub username: String,
+    pub connection_id: String,
+}
+
+impl Handler<RefreshPresence> for ChatServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: RefreshPresence, _: &mut Context<Self>) {
+        self.refresh_presence(&msg.username, &msg.connection_id);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::chat_server::chat_server::ChatServer;
+use crate::constants::DEFAULT_ROOM;
+use crate::models::rooms::{is_valid_room_name, RoomError};
+use actix::prelude::*;
+use serde_json::json;
+use termtalk_proto::chat::ChatType;
+use termtalk_proto::rooms::RoomRole;
+
+#[derive(Message)]
+#[rtype(result = "Result<(), RoomError>")]
+pub struct RenameRoom {
+    pub username: String,
+    pub room: String,
+    pub new_room: String,
+}
+
+impl Handler<RenameRoom> for ChatServer {
+    type Result = ResponseFuture<Result<(), RoomError>>;
+
+    fn handle(&mut self, msg: RenameRoom, _: &mut Context<Self>) -> Self::Result {
+        if msg.room == DEFAULT_ROOM {
+            return Box::pin(async move { Err(RoomError::DefaultRoom) });
+        }
+        if !is_valid_room_name(&msg.new_room) {
+            return Box::pin(async move { Err(RoomError::InvalidName(msg.new_room)) });
+        }
+        let room_guid = match self.redis.rooms_hash_map.get_room_guid_by_name(&msg.room) {
+            Some(val) => val,
+            None => return Box::pin(async move { Err(RoomError::NotFound(msg.room)) }),
+        };
+        if self.room_role(&msg.room, &msg.username) != RoomRole::Owner {
+            return Box::pin(async move { Err(RoomError::NotAllowed) });
+        }
+        if !self
+            .redis
+            .rooms_hash_map
+            .set_room_guid_by_name_if_absent(&msg.new_room, &room_guid)
+        {
+            return Box::pin(async move { Err(RoomError::AlreadyExists(msg.new_room)) });
+        }
+        self.redis.rooms_hash_map.remove_room(&msg.room);
+        if self
+            .redis
+            .archived_rooms_set
+            .remove_from_archived_rooms_set(&msg.room)
+        {
+            self.redis
+                .archived_rooms_set
+                .add_to_archived_rooms_set(&msg.new_room);
+        }
+        self.redis
+            .rooms_online_users_set
+            .rename_room_set(&msg.room, &msg.new_room);
+        self.redis.room_owners_hash_map.remove_room_owner(&msg.room);
+        self.redis
+            .room_owners_hash_map
+            .set_room_owner(&msg.new_room, &msg.username);
+        self.redis
+            .room_moderators_set
+            .rename_room_set(&msg.room, &msg.new_room);
+        self.redis
+            .room_banned_users_set
+            .rename_room_set(&msg.room, &msg.new_room);
+        self.redis
+            .room_muted_users_set
+            .rename_room_set(&msg.room, &msg.new_room);
+        if self
+            .redis
+            .private_rooms_set
+            .remove_from_private_rooms_set(&msg.room)
+        {
+            self.redis
+                .private_rooms_set
+                .add_to_private_rooms_set(&msg.new_room);
+        }
+        self.redis
+            .room_members_set
+            .rename_room_set(&msg.room, &msg.new_room);
+
+        let members = self.room_members(&msg.new_room);
+        if let Some(room_state) = self.rooms.remove(&msg.room) {
+            self.rooms.insert(msg.new_room.clone(), room_state);
+        }
+        for username in members {
+            if username != msg.username {
+                self.send_server_notice(
+                    &username,
+                    &format!(
+                        "Room {} was renamed to {} by {}",
+                        &msg.room, &msg.new_room, &msg.username
+                    ),
+                );
+            }
+            self.switch_room_sessions(&username, &msg.room, &msg.new_room, false);
+        }
+
+        let elastic = self.elastic.clone();
+        Box::pin(async move {
+            if let Err(error) = elastic
+                .messages
+                .rename_channel(&msg.room, &ChatType::Room, &msg.new_room)
+                .await
+            {
+                log::error!(
+                    "Failed to move messages from {} to {} in elastic: {}",
+                    &msg.room,
+                    &msg.new_room,
+                    error
+                );
+            }
+            elastic
+                .rooms
+                .update_room(&room_guid, json!({ "name": &msg.new_room }))
+                .await
+                .map_err(|error| {
+                    log::error!("Failed to rename room {} in elastic: {}", &msg.room, error);
+                    RoomError::Failed
+                })
+        })
+    }
+}
@@ -0,0 +1,28 @@
+use crate::chat_server::chat_server::ChatServer;
+use crate::utils::time_as_secs_since_epoch;
+use actix::prelude::*;
+
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct RevokeToken {
+    pub username: String,
+    pub jti: String,
+    pub family: String,
+    pub expires_at: u64,
+    pub token_lifetime_secs: u64,
+}
+
+impl Handler<RevokeToken> for ChatServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: RevokeToken, _: &mut Context<Self>) {
+        let now = time_as_secs_since_epoch();
+        self.redis
+            .revoked_tokens
+            .revoke_token(&msg.jti, msg.expires_at.saturating_sub(now) as usize);
+        self.redis
+            .revoked_tokens
+            .revoke_family(&msg.family, msg.token_lifetime_secs as usize);
+        self.close_token_sessions(&msg.username, &msg.family, "signed out");
+    }
+}
@@ -0,0 +1,24 @@
+use crate::chat_server::chat_server::ChatServer;
+use crate::utils::time_as_secs_since_epoch;
+use actix::prelude::*;
+
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct RevokeUserTokens {
+    pub username: String,
+    pub token_lifetime_secs: u64,
+    pub reason: String,
+}
+
+impl Handler<RevokeUserTokens> for ChatServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: RevokeUserTokens, _: &mut Context<Self>) {
+        self.redis.revoked_tokens.revoke_user_tokens(
+            &msg.username,
+            time_as_secs_since_epoch(),
+            msg.token_lifetime_secs as usize,
+        );
+        self.close_sessions(&msg.username, &msg.reason, "");
+    }
+}
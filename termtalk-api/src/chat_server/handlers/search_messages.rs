@@ -0,0 +1,35 @@
+use crate::chat_server::chat_server::ChatServer;
+use crate::constants::DEFAULT_SEARCH_SIZE;
+use crate::models::search::SearchQuery;
+use actix::prelude::*;
+use termtalk_proto::messages::MessageSearchHit;
+
+pub struct SearchMessages {
+    pub username: String,
+    pub query: String,
+}
+
+impl actix::Message for SearchMessages {
+    type Result = Result<Vec<MessageSearchHit>, String>;
+}
+
+impl Handler<SearchMessages> for ChatServer {
+    type Result = ResponseFuture<Result<Vec<MessageSearchHit>, String>>;
+
+    fn handle(&mut self, msg: SearchMessages, _: &mut Context<Self>) -> Self::Result {
+        let elastic = self.elastic.clone();
+        let rooms = self.readable_rooms(&msg.username);
+
+        Box::pin(async move {
+            let search_query = SearchQuery::parse(&msg.query).map_err(|e| e.to_string())?;
+            elastic
+                .messages
+                .search_messages(&search_query, &msg.username, &rooms, DEFAULT_SEARCH_SIZE)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to search messages for {}: {}", &msg.username, e);
+                    String::from("Something went wrong while searching messages")
+                })
+        })
+    }
+}
@@ -1,5 +1,6 @@
-use crate::chat_server::chat_server::{ChatServer, ChatType, MessageType};
+use crate::chat_server::chat_server::ChatServer;
 use actix::prelude::*;
+use termtalk_proto::chat::{ChatType, MessageType, Presence};
 
 #[derive(Message)]
 #[rtype(result = "()")]
@@ -9,6 +10,9 @@ pub struct SendClientMessage {
     pub chat_type: ChatType,
     pub msg_type: MessageType,
     pub recipient: String,
+    pub presence: Option<Presence>,
+    pub message_id: Option<String>,
+    pub created_at: Option<u64>,
 }
 
 impl Handler<SendClientMessage> for ChatServer {
@@ -16,21 +20,9 @@ impl Handler<SendClientMessage> for ChatServer {
 
     fn handle(&mut self, msg: SendClientMessage, _: &mut Context<Self>) {
         match msg.chat_type {
-            ChatType::Direct => self.send_message_to_direct(
-                &msg.recipient,
-                &msg.sender,
-                msg.msg.as_str(),
-                &msg.msg_type,
-            ),
-            ChatType::Room => self.send_message_to_room(
-                &msg.recipient,
-                &msg.sender,
-                msg.msg.as_str(),
-                &msg.msg_type,
-            ),
-            ChatType::Whisper => {
-                self.whisper_message_to_recipient(&msg.recipient, &msg.sender, msg.msg.as_str())
-            }
+            ChatType::Direct => self.send_message_to_direct(&msg),
+            ChatType::Room => self.send_message_to_room(&msg),
+            ChatType::Whisper => self.whisper_message_to_recipient(&msg),
             _ => {}
         };
     }
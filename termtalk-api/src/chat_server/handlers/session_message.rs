@@ -1,5 +1,10 @@
-use crate::chat_server::chat_server::{ChatServer, ChatType, MessageType, QueueMessage};
+use crate::chat_server::chat_server::{ChatServer, Message};
+use crate::models::messages::MessageDocument;
+use crate::utils::time_as_millis_since_epoch;
 use actix::prelude::*;
+use termtalk_proto::chat::{ChatType, MessageType, QueueMessage};
+use termtalk_proto::protocol::{ErrorCode, ServerEvent};
+use uuid::Uuid;
 
 #[derive(Message)]
 #[rtype(result = "()")]
@@ -15,15 +20,136 @@ impl Handler<SessionMessage> for ChatServer {
     type Result = ();
 
     fn handle(&mut self, msg: SessionMessage, _: &mut Context<Self>) {
+        if msg.chat_type == ChatType::Room
+            && self
+                .redis
+                .archived_rooms_set
+                .room_archived(&msg.channel_name)
+        {
+            self.send_server_error(
+                &msg.username,
+                ErrorCode::Archived,
+                &format!(
+                    "Room {} is archived and no longer accepts new messages",
+                    &msg.channel_name
+                ),
+            );
+            return;
+        }
+        if msg.chat_type == ChatType::Room
+            && self
+                .redis
+                .room_muted_users_set
+                .user_muted(&msg.channel_name, &msg.username)
+        {
+            self.send_server_error(
+                &msg.username,
+                ErrorCode::Muted,
+                &format!("You are muted in room {}", &msg.channel_name),
+            );
+            return;
+        }
+
+        let message_document = MessageDocument {
+            message_id: Uuid::new_v4().to_string(),
+            sender: msg.username.clone(),
+            channel: msg.channel_name.clone(),
+            chat_type: msg.chat_type.clone(),
+            msg_type: msg.msg_type.clone(),
+            msg: msg.msg.clone(),
+            created_at: time_as_millis_since_epoch(),
+            participants: MessageDocument::participants_for(
+                &msg.username,
+                &msg.channel_name,
+                &msg.chat_type,
+            ),
+        };
+        if msg.chat_type == ChatType::Direct {
+            for participant in &message_document.participants {
+                let reading = self.user_in_channel_anywhere(
+                    participant,
+                    &msg.channel_name,
+                    &ChatType::Direct,
+                    "",
+                );
+                if *participant != msg.username && !reading {
+                    self.redis
+                        .direct_unread_hash_map
+                        .increment_unread(participant, &msg.channel_name);
+                }
+            }
+        }
+        let offline_recipients: Vec<String> = match msg.chat_type {
+            ChatType::Direct | ChatType::Whisper => message_document
+                .participants
+                .iter()
+                .filter(|participant| {
+                    **participant != msg.username && !self.user_online(participant)
+                })
+                .cloned()
+                .collect(),
+            _ => vec![],
+        };
+        for recipient in &offline_recipients {
+            self.redis
+                .offline_messages_list
+                .queue_message(recipient, &message_document);
+        }
+        let message_id = message_document.message_id.clone();
+        let created_at = message_document.created_at;
+        let elastic = self.elastic.clone();
+        actix::spawn(async move {
+            if let Err(error) = elastic.messages.create_message(&message_document).await {
+                log::error!(
+                    "Failed to persist message {} to elastic: {}",
+                    message_document.message_id,
+                    error
+                );
+            }
+            if message_document.chat_type == ChatType::Direct {
+                if let Err(error) = elastic
+                    .direct_chats
+                    .touch_direct_chat(&message_document.channel, &message_document.participants)
+                    .await
+                {
+                    log::error!(
+                        "Failed to update direct chat {} in elastic: {}",
+                        &message_document.channel,
+                        error
+                    );
+                }
+            }
+        });
+
+        if !offline_recipients.is_empty() {
+            for sender_session in self.user_sessions(&msg.username) {
+                sender_session
+                    .addr
+                    .do_send(Message(ServerEvent::MessageReceived {
+                        sender: msg.username.clone(),
+                        channel: msg.channel_name.clone(),
+                        chat_type: msg.chat_type.clone(),
+                        msg_type: msg.msg_type.clone(),
+                        text: msg.msg.clone(),
+                        history: false,
+                        queued: true,
+                        message_id: Some(message_id.clone()),
+                        created_at: Some(created_at),
+                    }));
+            }
+            return;
+        }
+
         let chat_message = QueueMessage {
             sender: msg.username,
             msg: msg.msg,
             chat_type: msg.chat_type,
             msg_type: msg.msg_type,
             recipient: msg.channel_name,
+            presence: None,
+            message_id: Some(message_id),
+            created_at: Some(created_at),
         };
-        self.redis
-            .publish_chat_messages
-            .publish_to_channel(chat_message);
+        self.publish_chat_message(chat_message);
     }
 }
@@ -0,0 +1,71 @@
+use crate::chat_server::chat_server::ChatServer;
+use crate::models::rooms::RoomError;
+use actix::prelude::*;
+use termtalk_proto::chat::{ChatType, MessageType, QueueMessage};
+use termtalk_proto::rooms::RoomRole;
+
+#[derive(Message)]
+#[rtype(result = "Result<(), RoomError>")]
+pub struct SetRoomRole {
+    pub username: String,
+    pub room: String,
+    pub target: String,
+    pub role: RoomRole,
+}
+
+impl Handler<SetRoomRole> for ChatServer {
+    type Result = ResponseFuture<Result<(), RoomError>>;
+
+    fn handle(&mut self, msg: SetRoomRole, _: &mut Context<Self>) -> Self::Result {
+        if msg.target == msg.username {
+            return Box::pin(async move { Err(RoomError::InvalidTarget) });
+        }
+        if !self.room_exists(&msg.room) {
+            return Box::pin(async move { Err(RoomError::NotFound(msg.room)) });
+        }
+        if self.room_role(&msg.room, &msg.username) != RoomRole::Owner {
+            return Box::pin(async move { Err(RoomError::NotAllowed) });
+        }
+
+        match msg.role {
+            RoomRole::Moderator => {
+                self.redis
+                    .room_moderators_set
+                    .add_moderator(&msg.room, &msg.target);
+            }
+            RoomRole::Member => {
+                self.redis
+                    .room_moderators_set
+                    .remove_moderator(&msg.room, &msg.target);
+            }
+            RoomRole::Owner => return Box::pin(async move { Err(RoomError::NotAllowed) }),
+        };
+
+        let chat_message = QueueMessage {
+            sender: msg.username.clone(),
+            msg: format!(
+                "User {} is now a {} of room {}",
+                &msg.target, msg.role, &msg.room
+            ),
+            chat_type: ChatType::Room,
+            msg_type: MessageType::Server,
+            recipient: msg.room.clone(),
+            presence: None,
+            message_id: None,
+            created_at: None,
+        };
+        self.publish_chat_message(chat_message);
+
+        if !self.room_members(&msg.room).contains(&msg.target) {
+            self.send_server_notice(
+                &msg.target,
+                &format!(
+                    "You are now a {} of room {} thanks to {}",
+                    msg.role, &msg.room, &msg.username
+                ),
+            );
+        }
+
+        self.persist_room_moderation(&msg.room)
+    }
+}
@@ -1,8 +1,10 @@
-use crate::chat_server::chat_server::{ChatServer, ChatSessionState, ChatType};
+use crate::chat_server::chat_server::ChatServer;
 use actix::prelude::*;
+use termtalk_proto::chat::ChatType;
 
 pub struct UpdateSessionStatus {
     pub username: String,
+    pub connection_id: String,
     pub channel_name: String,
     pub chat_type: ChatType,
 }
@@ -15,9 +17,10 @@ impl Handler<UpdateSessionStatus> for ChatServer {
     type Result = MessageResult<UpdateSessionStatus>;
 
     fn handle(&mut self, msg: UpdateSessionStatus, _: &mut Context<Self>) -> Self::Result {
-        let mut user_session: &mut ChatSessionState = self.sessions.get_mut(&msg.username).unwrap();
-        user_session.channel_name = msg.channel_name.clone();
-        user_session.chat_type = msg.chat_type.clone();
+        if let Some(user_session) = self.user_session_mut(&msg.username, &msg.connection_id) {
+            user_session.channel_name = msg.channel_name.clone();
+            user_session.chat_type = msg.chat_type.clone();
+        }
 
         MessageResult(msg)
     }
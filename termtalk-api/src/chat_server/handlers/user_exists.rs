@@ -0,0 +1,28 @@
+use crate::chat_server::chat_server::ChatServer;
+use actix::prelude::*;
+
+pub struct UserExists {
+    pub username: String,
+}
+
+impl actix::Message for UserExists {
+    type Result = bool;
+}
+
+impl Handler<UserExists> for ChatServer {
+    type Result = ResponseFuture<bool>;
+
+    fn handle(&mut self, msg: UserExists, _: &mut Context<Self>) -> Self::Result {
+        let elastic = self.elastic.clone();
+
+        Box::pin(async move {
+            match elastic.users.retrieve_user(&msg.username).await {
+                Ok(val) => val.hits.total.value > 0,
+                Err(error) => {
+                    log::error!("Failed to look up user {}: {}", &msg.username, error);
+                    false
+                }
+            }
+        })
+    }
+}
@@ -1,5 +1,32 @@
+pub static SECRET_KEY: &str = "SECRET_KEY";
+pub static JWT_KEYS: &str = "JWT_KEYS";
+pub static JWT_ISSUER: &str = "JWT_ISSUER";
+pub static JWT_AUDIENCE: &str = "JWT_AUDIENCE";
+pub static JWT_LIFETIME_SECS: &str = "JWT_LIFETIME_SECS";
+pub static JWT_REFRESH_LIFETIME_SECS: &str = "JWT_REFRESH_LIFETIME_SECS";
+pub static DEFAULT_JWT_KID: &str = "default";
+pub static DEFAULT_JWT_ISSUER: &str = "termtalk-api";
+pub static DEFAULT_JWT_AUDIENCE: &str = "termtalk";
+pub static DEFAULT_JWT_LIFETIME_SECS: u64 = 60 * 15;
+pub static DEFAULT_JWT_REFRESH_LIFETIME_SECS: u64 = 60 * 60 * 24 * 30;
 pub static REDIS_HOST: &str = "REDIS_HOST";
 pub static REDIS_PORT: &str = "REDIS_PORT";
 pub static TERMTALK_API_HOST: &str = "TERMTALK_API_HOST";
 pub static TERMTALK_API_PORT: &str = "TERMTALK_API_PORT";
 pub static DEFAULT_ROOM: &str = "Main";
+pub static SCROLLBACK_SIZE: &str = "SCROLLBACK_SIZE";
+pub static DEFAULT_SCROLLBACK_SIZE: usize = 20;
+pub static SESSION_POLICY: &str = "SESSION_POLICY";
+pub static INSTANCE_ID: &str = "INSTANCE_ID";
+pub static DEFAULT_SEARCH_SIZE: usize = 20;
+pub static MAX_SEARCH_SIZE: usize = 100;
+pub static ROOM_INVITE_TTL_SECS: usize = 60 * 60 * 24;
+pub static DEFAULT_DIRECT_CHATS_SIZE: usize = 20;
+pub static MAX_OFFLINE_MESSAGES: isize = 500;
+pub static MAX_REPLAY_SIZE: usize = 500;
+pub static PRESENCE_LEASE_TTL_MILLIS: u64 = 30_000;
+pub static PRESENCE_REAP_INTERVAL_SECS: u64 = 15;
+pub static MESSAGE_BUS: &str = "MESSAGE_BUS";
+pub static STREAM_MAX_LEN: usize = 10_000;
+pub static STREAM_BLOCK_MILLIS: usize = 5_000;
+pub static STREAM_READ_COUNT: usize = 100;
@@ -1,12 +1,22 @@
 use std::future::{ready, Ready};
+use std::{error, fmt};
 
-use crate::jwt::lib::JwtToken;
+use crate::data_stores::redis::store::RedisStore;
+use crate::jwt::{
+    config::JwtConfig,
+    lib::{InvalidJwtToken, JwtToken, Payload},
+};
+use crate::models::roles::Role;
 use actix_web::body::EitherBody;
 use actix_web::dev::{self, ServiceRequest, ServiceResponse};
 use actix_web::dev::{Service, Transform};
-use actix_web::HttpMessage;
+use actix_web::http::header::{self, HeaderMap};
+use actix_web::{web, HttpMessage};
 use actix_web::{Error, HttpResponse};
 use futures_util::future::LocalBoxFuture;
+use serde_json::json;
+
+static REALM: &str = "termtalk";
 
 pub struct Authenticate;
 
@@ -43,7 +53,13 @@ where
     dev::forward_ready!(service);
 
     fn call(&self, request: ServiceRequest) -> Self::Future {
-        let exclude_paths = vec!["/healthcheck", "/register", "/login"];
+        let exclude_paths = vec![
+            "/healthcheck",
+            "/register",
+            "/login",
+            "/refresh",
+            "/.well-known/jwks.json",
+        ];
 
         if exclude_paths.contains(&request.path()) {
             log::debug!(
@@ -51,37 +67,183 @@ where
                 request.path()
             );
         } else {
-            let auth_header = request.headers().get("Authorization").unwrap();
-            let auth_header_parts = auth_header
-                .to_str()
-                .unwrap()
-                .split(" ")
-                .collect::<Vec<&str>>();
-            if auth_header_parts.len() != 2 {
-                let (request, _pl) = request.into_parts();
-                let response = HttpResponse::BadRequest()
-                    .body("Bad Request")
-                    .map_into_right_body();
-                log::debug!("The Authorization header had too many parts or too few parts");
-                return Box::pin(async { Ok(ServiceResponse::new(request, response)) });
-            }
-            let token_payload = JwtToken::verify(auth_header_parts[1]);
-            match token_payload {
+            match authenticate(&request) {
                 Ok(payload) => {
                     request.extensions_mut().insert(payload);
                 }
-                Err(e) => {
+                Err(error) => {
+                    log::debug!("Rejected request to {}: {}", request.path(), error);
                     let (request, _pl) = request.into_parts();
-                    let response = HttpResponse::BadRequest()
-                        .body("Bad Request")
-                        .map_into_right_body();
-                    log::debug!("JwtToken is invalid: {:?}", e);
+                    let response = error.error_response().map_into_right_body();
                     return Box::pin(async { Ok(ServiceResponse::new(request, response)) });
                 }
-            };
+            }
         }
 
         let res = self.service.call(request);
         Box::pin(async move { res.await.map(ServiceResponse::map_into_left_body) })
     }
 }
+
+#[derive(Debug, PartialEq)]
+pub enum AuthenticationError {
+    MissingToken,
+    BadHeader,
+    InvalidToken(InvalidJwtToken),
+    Revoked,
+    InsufficientRole(Role),
+}
+
+impl error::Error for AuthenticationError {}
+
+impl fmt::Display for AuthenticationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthenticationError::MissingToken => write!(f, "A bearer token is required"),
+            AuthenticationError::BadHeader => {
+                write!(f, "The Authorization header must be 'Bearer <token>'")
+            }
+            AuthenticationError::InvalidToken(e) => write!(f, "{}", e),
+            AuthenticationError::Revoked => write!(f, "The token has been revoked"),
+            AuthenticationError::InsufficientRole(role) => {
+                write!(f, "This requires the {} role", role)
+            }
+        }
+    }
+}
+
+impl AuthenticationError {
+    pub fn error_code(&self) -> Option<&'static str> {
+        match self {
+            AuthenticationError::MissingToken => None,
+            AuthenticationError::BadHeader => Some("invalid_request"),
+            AuthenticationError::InsufficientRole(_) => Some("insufficient_scope"),
+            _ => Some("invalid_token"),
+        }
+    }
+
+    pub fn www_authenticate(&self) -> String {
+        match self.error_code() {
+            Some(error_code) => format!(
+                "Bearer realm=\"{}\", error=\"{}\", error_description=\"{}\"",
+                REALM, error_code, self
+            ),
+            None => format!("Bearer realm=\"{}\"", REALM),
+        }
+    }
+
+    pub fn error_response(&self) -> HttpResponse {
+        let mut response = match self {
+            AuthenticationError::InsufficientRole(_) => HttpResponse::Forbidden(),
+            _ => HttpResponse::Unauthorized(),
+        };
+        response
+            .insert_header((header::WWW_AUTHENTICATE, self.www_authenticate()))
+            .json(json!({
+                "error": self.error_code().unwrap_or("unauthorized"),
+                "data": self.to_string(),
+            }))
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Result<&str, AuthenticationError> {
+    let auth_header = match headers.get(header::AUTHORIZATION) {
+        Some(val) => val.to_str().map_err(|_| AuthenticationError::BadHeader)?,
+        None => return Err(AuthenticationError::MissingToken),
+    };
+    match auth_header.split_once(' ') {
+        Some((scheme, token))
+            if scheme.eq_ignore_ascii_case("Bearer")
+                && !token.is_empty()
+                && !token.contains(' ') =>
+        {
+            Ok(token)
+        }
+        _ => Err(AuthenticationError::BadHeader),
+    }
+}
+
+fn authenticate(request: &ServiceRequest) -> Result<Payload, AuthenticationError> {
+    let token = bearer_token(request.headers())?;
+    let jwt_config = request.app_data::<web::Data<JwtConfig>>().unwrap();
+    let payload = JwtToken::verify(token, jwt_config).map_err(AuthenticationError::InvalidToken)?;
+    let revoked_tokens = &request
+        .app_data::<web::Data<RedisStore>>()
+        .unwrap()
+        .revoked_tokens;
+    if revoked_tokens.token_revoked(&payload.jti)
+        || revoked_tokens.family_revoked(&payload.fam)
+        || revoked_tokens.user_tokens_revoked(&payload.username, payload.iat)
+    {
+        return Err(AuthenticationError::Revoked);
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jwt::config::JwtKeyring;
+    use crate::models::users::User;
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn test_rejections_are_unauthorized_with_bearer_challenge() {
+        let jwt_config = JwtConfig {
+            issuer: String::from("termtalk-api"),
+            audience: String::from("termtalk"),
+            lifetime_secs: 900,
+            refresh_lifetime_secs: 3600,
+            keyring: "2026-10:SECRET_KEY".parse::<JwtKeyring>().unwrap(),
+        };
+        let user = User {
+            id: String::from("1"),
+            username: String::from("zalir"),
+            email: String::from("mektievp@gmail.com"),
+            password: String::from(""),
+            roles: vec![],
+        };
+        let expired = JwtToken::create_from_user(user, Some(1516239022), &jwt_config);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(jwt_config))
+                .wrap(Authenticate)
+                .route("/search", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let cases = [
+            (None, "Bearer realm=\"termtalk\"", "unauthorized"),
+            (
+                Some(String::from("Token abc")),
+                "Bearer realm=\"termtalk\", error=\"invalid_request\", error_description=\"The Authorization header must be 'Bearer <token>'\"",
+                "invalid_request",
+            ),
+            (
+                Some(String::from("Bearer not.a.token")),
+                "Bearer realm=\"termtalk\", error=\"invalid_token\", error_description=\"The token is malformed\"",
+                "invalid_token",
+            ),
+            (
+                Some(format!("bearer {}", expired.token)),
+                "Bearer realm=\"termtalk\", error=\"invalid_token\", error_description=\"The token has expired\"",
+                "invalid_token",
+            ),
+        ];
+        for (auth_header, challenge, error_code) in cases {
+            let mut req = test::TestRequest::get().uri("/search");
+            if let Some(auth_header) = auth_header {
+                req = req.insert_header((header::AUTHORIZATION, auth_header));
+            }
+            let resp = test::call_service(&app, req.to_request()).await;
+
+            assert_eq!(StatusCode::UNAUTHORIZED, resp.status());
+            assert_eq!(
+                challenge,
+                resp.headers().get(header::WWW_AUTHENTICATE).unwrap()
+            );
+            let body: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(error_code, body["error"]);
+        }
+    }
+}
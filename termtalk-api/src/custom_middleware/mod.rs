@@ -1 +1,2 @@
 pub mod auth;
+pub mod require_role;
@@ -0,0 +1,126 @@
+use std::future::{ready, Ready};
+
+use crate::custom_middleware::auth::AuthenticationError;
+use crate::jwt::lib::Payload;
+use crate::models::roles::Role;
+use actix_web::body::EitherBody;
+use actix_web::dev::{self, ServiceRequest, ServiceResponse};
+use actix_web::dev::{Service, Transform};
+use actix_web::Error;
+use actix_web::HttpMessage;
+use futures_util::future::LocalBoxFuture;
+
+pub struct RequireRole(pub Role);
+
+impl<S, B> Transform<S, ServiceRequest> for RequireRole
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequireRoleMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireRoleMiddleware {
+            service,
+            role: self.0,
+        }))
+    }
+}
+pub struct RequireRoleMiddleware<S> {
+    service: S,
+    role: Role,
+}
+
+impl<S, B> Service<ServiceRequest> for RequireRoleMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let granted = match request.extensions().get::<Payload>() {
+            Some(payload) => self.role.granted_by(&payload.roles),
+            None => false,
+        };
+        if !granted {
+            log::debug!(
+                "Rejected request to {} without the {} role",
+                request.path(),
+                self.role
+            );
+            let (request, _pl) = request.into_parts();
+            let response = AuthenticationError::InsufficientRole(self.role)
+                .error_response()
+                .map_into_right_body();
+            return Box::pin(async { Ok(ServiceResponse::new(request, response)) });
+        }
+
+        let res = self.service.call(request);
+        Box::pin(async move { res.await.map(ServiceResponse::map_into_left_body) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::dev::Service;
+    use actix_web::http::{header, StatusCode};
+    use actix_web::{test, web, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn test_require_role() {
+        let app = test::init_service(
+            App::new()
+                .wrap_fn(|request, srv| {
+                    if let Some(roles) = request.headers().get("Test-Roles") {
+                        let roles: Vec<Role> = serde_json::from_slice(roles.as_bytes()).unwrap();
+                        request.extensions_mut().insert(Payload {
+                            roles,
+                            ..Default::default()
+                        });
+                    }
+                    srv.call(request)
+                })
+                .service(
+                    web::resource("/admin")
+                        .wrap(RequireRole(Role::Moderator))
+                        .to(HttpResponse::Ok),
+                ),
+        )
+        .await;
+
+        let cases = [
+            (Some(r#"["admin"]"#), StatusCode::OK),
+            (Some(r#"["moderator"]"#), StatusCode::OK),
+            (Some(r#"["user"]"#), StatusCode::FORBIDDEN),
+            (Some("[]"), StatusCode::FORBIDDEN),
+            (None, StatusCode::FORBIDDEN),
+        ];
+        for (roles, status) in cases {
+            let mut req = test::TestRequest::get().uri("/admin");
+            if let Some(roles) = roles {
+                req = req.insert_header(("Test-Roles", roles));
+            }
+            let resp = test::call_service(&app, req.to_request()).await;
+
+            assert_eq!(status, resp.status());
+            if status == StatusCode::FORBIDDEN {
+                assert_eq!(
+                    "Bearer realm=\"termtalk\", error=\"insufficient_scope\", error_description=\"This requires the moderator role\"",
+                    resp.headers().get(header::WWW_AUTHENTICATE).unwrap()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::models::direct_chats::DirectChatDocument;
+use crate::models::elastic::TermQuery;
+use crate::utils::time_as_millis_since_epoch;
+use elasticsearch;
+use serde_json::{json, Value};
+
+#[derive(Clone, Debug)]
+pub struct DirectChatsElasticStore {
+    elastic: elasticsearch::Elasticsearch,
+}
+
+static DIRECT_CHATS: &str = "direct_chats";
+
+impl DirectChatsElasticStore {
+    pub fn new(elastic: elasticsearch::Elasticsearch) -> DirectChatsElasticStore {
+        Self { elastic }
+    }
+
+    pub async fn open_direct_chat(
+        &self,
+        channel_name: &str,
+        participants: &[String],
+    ) -> Result<(), elasticsearch::Error> {
+        self.upsert_direct_chat(
+            channel_name,
+            participants,
+            json!({ "participants": participants }),
+        )
+        .await
+    }
+
+    pub async fn touch_direct_chat(
+        &self,
+        channel_name: &str,
+        participants: &[String],
+    ) -> Result<(), elasticsearch::Error> {
+        self.upsert_direct_chat(
+            channel_name,
+            participants,
+            json!({ "last_activity_at": time_as_millis_since_epoch() }),
+        )
+        .await
+    }
+
+    async fn upsert_direct_chat(
+        &self,
+        channel_name: &str,
+        participants: &[String],
+        fields: Value,
+    ) -> Result<(), elasticsearch::Error> {
+        let now = time_as_millis_since_epoch();
+        let direct_chat_document = DirectChatDocument {
+            name: channel_name.to_owned(),
+            participants: participants.to_vec(),
+            created_at: now,
+            last_activity_at: now,
+        };
+        let resp_body = self
+            .elastic
+            .update(elasticsearch::UpdateParts::IndexId(
+                DIRECT_CHATS,
+                channel_name,
+            ))
+            .body(json!({ "doc": fields, "upsert": direct_chat_document }))
+            .send()
+            .await?;
+
+        resp_body.error_for_status_code()?;
+        Ok(())
+    }
+
+    pub async fn recent_direct_chats(
+        &self,
+        username: &str,
+        size: usize,
+    ) -> Result<Vec<DirectChatDocument>, elasticsearch::Error> {
+        let resp_body = self
+            .elastic
+            .search(elasticsearch::SearchParts::Index(&[DIRECT_CHATS]))
+            .body(json!({
+                "query": {
+                    "bool": {
+                        "filter": [
+                            { "term": { "participants": username } }
+                        ],
+                        "must_not": [
+                            { "exists": { "field": "deleted_at" } }
+                        ]
+                    }
+                },
+                "sort": [
+                    { "last_activity_at": { "order": "desc" } }
+                ],
+                "size": size
+            }))
+            .send()
+            .await?;
+
+        let resp_result: elasticsearch::http::response::Response =
+            match resp_body.error_for_status_code() {
+                Ok(val) => val,
+                Err(error) => return Err(error),
+            };
+
+        let search_result = resp_result.json::<TermQuery<DirectChatDocument>>().await?;
+        Ok(search_result
+            .hits
+            .hits
+            .into_iter()
+            .map(|hit| hit._source)
+            .collect())
+    }
+}
@@ -0,0 +1,259 @@
+use crate::models::elastic::TermQuery;
+use crate::models::messages::{CreateMessageResult, MessageDocument};
+use crate::models::search::SearchQuery;
+use elasticsearch;
+use serde_json::{json, Value};
+use termtalk_proto::chat::ChatType;
+use termtalk_proto::messages::MessageSearchHit;
+
+#[derive(Clone, Debug)]
+pub struct MessagesElasticStore {
+    elastic: elasticsearch::Elasticsearch,
+}
+
+static MESSAGES: &str = "messages";
+
+impl MessagesElasticStore {
+    pub fn new(elastic: elasticsearch::Elasticsearch) -> MessagesElasticStore {
+        Self { elastic }
+    }
+
+    pub async fn create_message(
+        &self,
+        message: &MessageDocument,
+    ) -> Result<CreateMessageResult, elasticsearch::Error> {
+        let resp_body = self
+            .elastic
+            .index(elasticsearch::IndexParts::IndexId(
+                MESSAGES,
+                &message.message_id,
+            ))
+            .body(json!(message))
+            .send()
+            .await?;
+
+        let resp_result: elasticsearch::http::response::Response =
+            match resp_body.error_for_status_code() {
+                Ok(val) => val,
+                Err(error) => return Err(error),
+            };
+
+        resp_result.json::<CreateMessageResult>().await
+    }
+
+    pub async fn recent_messages(
+        &self,
+        channel: &str,
+        chat_type: &ChatType,
+        size: usize,
+        since: Option<u64>,
+    ) -> Result<Vec<MessageDocument>, elasticsearch::Error> {
+        let mut filters = vec![
+            json!({ "term": { "channel": channel } }),
+            json!({ "term": { "chat_type": chat_type } }),
+        ];
+        if let Some(since) = since {
+            filters.push(json!({ "range": { "created_at": { "gte": since } } }));
+            self.elastic
+                .indices()
+                .refresh(elasticsearch::indices::IndicesRefreshParts::Index(&[
+                    MESSAGES,
+                ]))
+                .send()
+                .await?;
+        }
+
+        let resp_body = self
+            .elastic
+            .search(elasticsearch::SearchParts::Index(&[MESSAGES]))
+            .body(json!({
+                "query": {
+                    "bool": {
+                        "filter": filters
+                    }
+                },
+                "sort": [
+                    { "created_at": { "order": "desc" } }
+                ],
+                "size": size
+            }))
+            .send()
+            .await?;
+
+        let resp_result: elasticsearch::http::response::Response =
+            match resp_body.error_for_status_code() {
+                Ok(val) => val,
+                Err(error) => return Err(error),
+            };
+
+        let search_result = resp_result.json::<TermQuery<MessageDocument>>().await?;
+        let mut messages: Vec<MessageDocument> = search_result
+            .hits
+            .hits
+            .into_iter()
+            .map(|hit| hit._source)
+            .collect();
+        messages.reverse();
+
+        Ok(messages)
+    }
+
+    pub async fn rename_channel(
+        &self,
+        channel: &str,
+        chat_type: &ChatType,
+        new_channel: &str,
+    ) -> Result<(), elasticsearch::Error> {
+        let resp_body = self
+            .elastic
+            .update_by_query(elasticsearch::UpdateByQueryParts::Index(&[MESSAGES]))
+            .body(json!({
+                "query": {
+                    "bool": {
+                        "filter": [
+                            { "term": { "channel": channel } },
+                            { "term": { "chat_type": chat_type } }
+                        ]
+                    }
+                },
+                "script": {
+                    "source": "ctx._source.channel = params.new_channel",
+                    "params": { "new_channel": new_channel }
+                }
+            }))
+            .send()
+            .await?;
+
+        resp_body.error_for_status_code()?;
+        Ok(())
+    }
+
+    pub async fn search_messages(
+        &self,
+        search_query: &SearchQuery,
+        username: &str,
+        rooms: &[String],
+        size: usize,
+    ) -> Result<Vec<MessageSearchHit>, elasticsearch::Error> {
+        let resp_body = self
+            .elastic
+            .search(elasticsearch::SearchParts::Index(&[MESSAGES]))
+            .body(search_messages_body(search_query, username, rooms, size))
+            .send()
+            .await?;
+
+        let resp_result: elasticsearch::http::response::Response =
+            match resp_body.error_for_status_code() {
+                Ok(val) => val,
+                Err(error) => return Err(error),
+            };
+
+        let search_result = resp_result.json::<TermQuery<MessageDocument>>().await?;
+        let hits: Vec<MessageSearchHit> = search_result
+            .hits
+            .hits
+            .into_iter()
+            .map(|hit| MessageSearchHit {
+                message_id: hit._source.message_id,
+                sender: hit._source.sender,
+                channel: hit._source.channel,
+                chat_type: hit._source.chat_type,
+                msg_type: hit._source.msg_type,
+                msg: hit._source.msg,
+                created_at: hit._source.created_at,
+                score: hit._score,
+            })
+            .collect();
+
+        Ok(hits)
+    }
+}
+
+fn search_messages_body(
+    search_query: &SearchQuery,
+    username: &str,
+    rooms: &[String],
+    size: usize,
+) -> Value {
+    let mut filters: Vec<Value> = vec![json!({
+        "bool": {
+            "should": [
+                {
+                    "bool": {
+                        "filter": [
+                            { "term": { "chat_type": ChatType::Room } },
+                            { "terms": { "channel": rooms } }
+                        ]
+                    }
+                },
+                { "term": { "participants": username } }
+            ],
+            "minimum_should_match": 1
+        }
+    })];
+
+    if let Some(from) = &search_query.from {
+        filters.push(json!({ "term": { "sender": from } }));
+    }
+    if let Some(channel) = &search_query.channel {
+        filters.push(json!({ "term": { "channel": channel } }));
+    }
+    if search_query.before.is_some() || search_query.after.is_some() {
+        let mut range = json!({ "format": "yyyy-MM-dd" });
+        if let Some(before) = &search_query.before {
+            range["lt"] = json!(before);
+        }
+        if let Some(after) = &search_query.after {
+            range["gte"] = json!(after);
+        }
+        filters.push(json!({ "range": { "created_at": range } }));
+    }
+
+    let mut body = json!({
+        "query": {
+            "bool": {
+                "filter": filters
+            }
+        },
+        "size": size
+    });
+    if search_query.text.is_empty() {
+        body["sort"] = json!([{ "created_at": { "order": "desc" } }]);
+    } else {
+        body["query"]["bool"]["must"] = json!([{ "match": { "msg": &search_query.text } }]);
+    }
+
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_only_matches_readable_rooms() {
+        let search_query = SearchQuery::parse("in:Secret launch").unwrap();
+        let rooms = vec![String::from("Main")];
+        let body = search_messages_body(&search_query, "mallory", &rooms, 20);
+
+        let visibility = &body["query"]["bool"]["filter"][0]["bool"]["should"];
+        assert_eq!(
+            json!([
+                {
+                    "bool": {
+                        "filter": [
+                            { "term": { "chat_type": "Room" } },
+                            { "terms": { "channel": ["Main"] } }
+                        ]
+                    }
+                },
+                { "term": { "participants": "mallory" } }
+            ]),
+            *visibility
+        );
+        assert_eq!(
+            json!({ "term": { "channel": "Secret" } }),
+            body["query"]["bool"]["filter"][1]
+        );
+    }
+}
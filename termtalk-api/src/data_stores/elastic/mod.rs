@@ -1,2 +1,5 @@
+pub mod direct_chats;
+pub mod messages;
+pub mod rooms;
 pub mod store;
 pub mod users;
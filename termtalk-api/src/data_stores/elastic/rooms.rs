@@ -0,0 +1,102 @@
+use crate::models::elastic::{DocumentMetadata, TermQuery};
+use crate::models::rooms::{CreateRoomResult, RoomDocument};
+use crate::utils::time_as_millis_since_epoch;
+use elasticsearch;
+use serde_json::{json, Value};
+
+#[derive(Clone, Debug)]
+pub struct RoomsElasticStore {
+    elastic: elasticsearch::Elasticsearch,
+}
+
+static ROOMS: &str = "rooms";
+
+impl RoomsElasticStore {
+    pub fn new(elastic: elasticsearch::Elasticsearch) -> RoomsElasticStore {
+        Self { elastic }
+    }
+
+    pub async fn create_room(
+        &self,
+        room_guid: &str,
+        room_name: &str,
+        created_by: &str,
+        private: bool,
+    ) -> Result<CreateRoomResult, elasticsearch::Error> {
+        let room_document = RoomDocument {
+            name: room_name.to_owned(),
+            created_by: created_by.to_owned(),
+            created_at: time_as_millis_since_epoch(),
+            archived_at: None,
+            deleted_at: None,
+            moderators: vec![],
+            banned: vec![],
+            muted: vec![],
+            private,
+            members: if private {
+                vec![created_by.to_owned()]
+            } else {
+                vec![]
+            },
+        };
+        let resp_body = self
+            .elastic
+            .index(elasticsearch::IndexParts::IndexId(ROOMS, room_guid))
+            .body(json!(room_document))
+            .send()
+            .await?;
+
+        let resp_result: elasticsearch::http::response::Response =
+            match resp_body.error_for_status_code() {
+                Ok(val) => val,
+                Err(error) => return Err(error),
+            };
+
+        resp_result.json::<CreateRoomResult>().await
+    }
+
+    pub async fn update_room(
+        &self,
+        room_guid: &str,
+        fields: Value,
+    ) -> Result<(), elasticsearch::Error> {
+        let resp_body = self
+            .elastic
+            .update(elasticsearch::UpdateParts::IndexId(ROOMS, room_guid))
+            .body(json!({ "doc": fields }))
+            .send()
+            .await?;
+
+        resp_body.error_for_status_code()?;
+        Ok(())
+    }
+
+    pub async fn list_rooms(
+        &self,
+    ) -> Result<Vec<DocumentMetadata<RoomDocument>>, elasticsearch::Error> {
+        let resp_body = self
+            .elastic
+            .search(elasticsearch::SearchParts::Index(&[ROOMS]))
+            .body(json!({
+                "query": {
+                    "bool": {
+                        "must_not": [
+                            { "exists": { "field": "deleted_at" } }
+                        ]
+                    }
+                },
+                "size": 10000
+            }))
+            .send()
+            .await?;
+
+        let resp_result: elasticsearch::http::response::Response =
+            match resp_body.error_for_status_code() {
+                Ok(val) => val,
+                Err(error) => return Err(error),
+            };
+
+        let search_result = resp_result.json::<TermQuery<RoomDocument>>().await?;
+        Ok(search_result.hits.hits)
+    }
+}
@@ -1,14 +1,23 @@
-use super::users::UsersElasticStore;
+use super::{
+    direct_chats::DirectChatsElasticStore, messages::MessagesElasticStore,
+    rooms::RoomsElasticStore, users::UsersElasticStore,
+};
 
 #[derive(Clone, Debug)]
 pub struct ElasticStore {
     pub users: UsersElasticStore,
+    pub messages: MessagesElasticStore,
+    pub rooms: RoomsElasticStore,
+    pub direct_chats: DirectChatsElasticStore,
 }
 
 impl ElasticStore {
     pub fn new(elastic_client: elasticsearch::Elasticsearch) -> ElasticStore {
         ElasticStore {
             users: UsersElasticStore::new(elastic_client.clone()),
+            messages: MessagesElasticStore::new(elastic_client.clone()),
+            rooms: RoomsElasticStore::new(elastic_client.clone()),
+            direct_chats: DirectChatsElasticStore::new(elastic_client.clone()),
         }
     }
 }
@@ -1,8 +1,9 @@
 use crate::models::elastic::TermQuery;
-use crate::models::request_models::RegistrationForm;
+use crate::models::roles::Role;
 use crate::models::users::{RegisterUserResult, UserDocument};
 use actix_web::web;
 use elasticsearch;
+use termtalk_proto::request_models::RegistrationForm;
 extern crate bcrypt;
 use bcrypt::{hash, DEFAULT_COST};
 use serde_json::json;
@@ -80,4 +81,23 @@ impl UsersElasticStore {
 
         resp_result.json::<RegisterUserResult>().await
     }
+
+    pub async fn update_user_roles(
+        &self,
+        user_id: &str,
+        roles: &[Role],
+    ) -> Result<(), elasticsearch::Error> {
+        let resp_body = self
+            .elastic
+            .update(elasticsearch::UpdateParts::IndexId(USERS, user_id))
+            .body(json!({
+                "doc": {
+                    "roles": roles,
+                }
+            }))
+            .send()
+            .await?;
+
+        resp_body.error_for_status_code().map(|_| ())
+    }
 }
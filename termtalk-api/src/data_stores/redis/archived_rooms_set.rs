@@ -0,0 +1,42 @@
+use super::store::{RedisSet, RedisSetFns, RedisUtilityFunc};
+
+static ARCHIVED_ROOMS: &str = "ARCHIVED_ROOMS";
+
+#[derive(Clone, Debug)]
+pub struct ArchivedRoomsSet {
+    redis: redis::Client,
+}
+
+impl ArchivedRoomsSet {
+    pub fn new(redis_client: redis::Client) -> ArchivedRoomsSet {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for ArchivedRoomsSet {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RedisSet for ArchivedRoomsSet {
+    fn set_name() -> String {
+        ARCHIVED_ROOMS.to_owned()
+    }
+}
+
+impl ArchivedRoomsSet {
+    pub fn add_to_archived_rooms_set(&self, room: &str) -> bool {
+        self.sadd(ARCHIVED_ROOMS, room)
+    }
+
+    pub fn remove_from_archived_rooms_set(&self, room: &str) -> bool {
+        self.srem(ARCHIVED_ROOMS, room)
+    }
+
+    pub fn room_archived(&self, room: &str) -> bool {
+        self.sismember(ARCHIVED_ROOMS, room)
+    }
+}
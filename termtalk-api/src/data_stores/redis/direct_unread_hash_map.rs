@@ -0,0 +1,54 @@
+use super::store::{RedisHashMap, RedisHashMapFns, RedisUtilityFunc};
+
+pub static DIRECT_UNREAD_HASH_MAP: &str = "_DIRECT_UNREAD_HASH_MAP";
+
+#[derive(Clone, Debug)]
+pub struct DirectUnreadHashMap {
+    redis: redis::Client,
+}
+
+impl DirectUnreadHashMap {
+    pub fn new(redis_client: redis::Client) -> DirectUnreadHashMap {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for DirectUnreadHashMap {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RedisHashMap for DirectUnreadHashMap {
+    fn hash_map_name() -> String {
+        DIRECT_UNREAD_HASH_MAP.to_owned()
+    }
+}
+
+impl DirectUnreadHashMap {
+    pub fn increment_unread(&self, username: &str, channel_name: &str) -> i64 {
+        self.hincr(
+            &format!("{}{}", username, DIRECT_UNREAD_HASH_MAP),
+            channel_name,
+            1,
+        )
+    }
+
+    pub fn get_unread(&self, username: &str, channel_name: &str) -> usize {
+        self.hget(
+            &format!("{}{}", username, DIRECT_UNREAD_HASH_MAP),
+            channel_name,
+        )
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(0)
+    }
+
+    pub fn clear_unread(&self, username: &str, channel_name: &str) -> bool {
+        self.hdel(
+            &format!("{}{}", username, DIRECT_UNREAD_HASH_MAP),
+            channel_name,
+        )
+    }
+}
@@ -1,5 +1,19 @@
+pub mod archived_rooms_set;
+pub mod direct_unread_hash_map;
+pub mod offline_messages_list;
+pub mod presence_leases;
+pub mod private_rooms_set;
 pub mod publish_chat_messages;
+pub mod refresh_tokens;
+pub mod revoked_tokens;
+pub mod room_banned_users_set;
+pub mod room_invites;
+pub mod room_members_set;
+pub mod room_moderators_set;
+pub mod room_muted_users_set;
+pub mod room_owners_hash_map;
 pub mod rooms_hash_map;
 pub mod rooms_online_users_set;
+pub mod session_instances_hash_map;
 pub mod store;
 pub mod users_online_set;
@@ -0,0 +1,50 @@
+use super::store::{RedisList, RedisListFns, RedisUtilityFunc};
+use crate::constants::MAX_OFFLINE_MESSAGES;
+use crate::models::messages::MessageDocument;
+
+pub static OFFLINE_MESSAGES_LIST: &str = "_OFFLINE_MESSAGES_LIST";
+
+#[derive(Clone, Debug)]
+pub struct OfflineMessagesList {
+    redis: redis::Client,
+}
+
+impl OfflineMessagesList {
+    pub fn new(redis_client: redis::Client) -> OfflineMessagesList {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for OfflineMessagesList {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RedisList for OfflineMessagesList {
+    fn list_name() -> String {
+        OFFLINE_MESSAGES_LIST.to_owned()
+    }
+}
+
+impl OfflineMessagesList {
+    fn user_list_name(username: &str) -> String {
+        format!("{}{}", username, Self::list_name())
+    }
+
+    pub fn queue_message(&self, username: &str, message: &MessageDocument) -> usize {
+        let list_name = Self::user_list_name(username);
+        let queued = self.rpush(&list_name, &serde_json::to_string(message).unwrap());
+        self.ltrim(&list_name, -MAX_OFFLINE_MESSAGES, -1);
+        queued
+    }
+
+    pub fn drain_messages(&self, username: &str) -> Vec<MessageDocument> {
+        self.drain(&Self::user_list_name(username))
+            .iter()
+            .filter_map(|message| serde_json::from_str(message).ok())
+            .collect()
+    }
+}
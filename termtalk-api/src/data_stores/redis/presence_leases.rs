@@ -0,0 +1,140 @@
+use super::store::RedisUtilityFunc;
+use redis::Commands;
+use std::collections::HashSet;
+use termtalk_proto::chat::ChatType;
+
+static PRESENCE_LEASES: &str = "PRESENCE_LEASES_";
+
+static PRESENCE_LEASE_ROOMS: &str = "PRESENCE_LEASE_ROOMS_";
+
+static ROOM_LEASE: &str = "room:";
+
+static DIRECT_LEASE: &str = "direct:";
+
+static REMOVE_EXPIRED_LEASES_SCRIPT: &str = r#"
+local expired = redis.call("ZRANGEBYSCORE", KEYS[1], "-inf", ARGV[1])
+if #expired > 0 then
+    redis.call("ZREM", KEYS[1], unpack(expired))
+    redis.call("HDEL", KEYS[2], unpack(expired))
+end
+return expired
+"#;
+
+pub fn lease_channel(chat_type: &ChatType, channel_name: &str) -> Option<String> {
+    match chat_type {
+        ChatType::Room => Some(format!("{}{}", ROOM_LEASE, channel_name)),
+        ChatType::Direct => Some(format!("{}{}", DIRECT_LEASE, channel_name)),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PresenceLeases {
+    redis: redis::Client,
+}
+
+impl PresenceLeases {
+    pub fn new(redis_client: redis::Client) -> PresenceLeases {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for PresenceLeases {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl PresenceLeases {
+    pub fn refresh_lease(
+        &self,
+        username: &str,
+        connection_id: &str,
+        channel: Option<&str>,
+        expires_at: u64,
+    ) {
+        let rooms_key = format!("{}{}", PRESENCE_LEASE_ROOMS, username);
+        let mut pipe = redis::pipe();
+        pipe.atomic().zadd(
+            format!("{}{}", PRESENCE_LEASES, username),
+            connection_id,
+            expires_at,
+        );
+        match channel {
+            Some(channel) => pipe.hset(&rooms_key, connection_id, channel),
+            None => pipe.hdel(&rooms_key, connection_id),
+        };
+        let _: () = pipe.query(&mut self.get_connection()).unwrap();
+    }
+
+    pub fn remove_lease(&self, username: &str, connection_id: &str) {
+        let _: () = redis::pipe()
+            .atomic()
+            .zrem(format!("{}{}", PRESENCE_LEASES, username), connection_id)
+            .hdel(
+                format!("{}{}", PRESENCE_LEASE_ROOMS, username),
+                connection_id,
+            )
+            .query(&mut self.get_connection())
+            .unwrap();
+    }
+
+    pub fn remove_expired_leases(&self, username: &str, now: u64) -> Vec<String> {
+        redis::Script::new(REMOVE_EXPIRED_LEASES_SCRIPT)
+            .key(format!("{}{}", PRESENCE_LEASES, username))
+            .key(format!("{}{}", PRESENCE_LEASE_ROOMS, username))
+            .arg(now)
+            .invoke(&mut self.get_connection())
+            .unwrap()
+    }
+
+    pub fn user_online(&self, username: &str, now: u64) -> bool {
+        let live: usize = self
+            .get_connection()
+            .zcount(format!("{}{}", PRESENCE_LEASES, username), now + 1, "+inf")
+            .unwrap();
+        live > 0
+    }
+
+    fn live_channels(&self, username: &str, now: u64) -> Vec<(String, String)> {
+        let mut connection = self.get_connection();
+        let live: Vec<String> = connection
+            .zrangebyscore(format!("{}{}", PRESENCE_LEASES, username), now + 1, "+inf")
+            .unwrap();
+        if live.is_empty() {
+            return vec![];
+        }
+        let channels: Vec<Option<String>> = redis::cmd("HMGET")
+            .arg(format!("{}{}", PRESENCE_LEASE_ROOMS, username))
+            .arg(&live)
+            .query(&mut connection)
+            .unwrap();
+        live.into_iter()
+            .zip(channels)
+            .filter_map(|(connection_id, channel)| Some((connection_id, channel?)))
+            .collect()
+    }
+
+    pub fn live_rooms(&self, username: &str, now: u64) -> HashSet<String> {
+        self.live_channels(username, now)
+            .into_iter()
+            .filter_map(|(_, channel)| channel.strip_prefix(ROOM_LEASE).map(|room| room.to_owned()))
+            .collect()
+    }
+
+    pub fn channel_live(
+        &self,
+        username: &str,
+        channel: &str,
+        except_connection_id: &str,
+        now: u64,
+    ) -> bool {
+        self.live_channels(username, now)
+            .iter()
+            .any(|(connection_id, live_channel)| {
+                connection_id != except_connection_id && live_channel == channel
+            })
+    }
+}
@@ -0,0 +1,42 @@
+use super::store::{RedisSet, RedisSetFns, RedisUtilityFunc};
+
+static PRIVATE_ROOMS: &str = "PRIVATE_ROOMS";
+
+#[derive(Clone, Debug)]
+pub struct PrivateRoomsSet {
+    redis: redis::Client,
+}
+
+impl PrivateRoomsSet {
+    pub fn new(redis_client: redis::Client) -> PrivateRoomsSet {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for PrivateRoomsSet {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RedisSet for PrivateRoomsSet {
+    fn set_name() -> String {
+        PRIVATE_ROOMS.to_owned()
+    }
+}
+
+impl PrivateRoomsSet {
+    pub fn add_to_private_rooms_set(&self, room: &str) -> bool {
+        self.sadd(PRIVATE_ROOMS, room)
+    }
+
+    pub fn remove_from_private_rooms_set(&self, room: &str) -> bool {
+        self.srem(PRIVATE_ROOMS, room)
+    }
+
+    pub fn room_private(&self, room: &str) -> bool {
+        self.sismember(PRIVATE_ROOMS, room)
+    }
+}
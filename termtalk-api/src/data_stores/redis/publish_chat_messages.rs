@@ -1,17 +1,26 @@
 use super::store::{RedisPubSub, RedisPubSubFns, RedisUtilityFunc};
-use crate::chat_server::chat_server::QueueMessage;
+use crate::constants::{STREAM_BLOCK_MILLIS, STREAM_MAX_LEN, STREAM_READ_COUNT};
+use crate::models::message_bus::MessageBus;
+use redis::streams::{StreamMaxlen, StreamReadOptions, StreamReadReply};
+use redis::Commands;
 
 pub static CHAT_MESSAGES: &str = "CHAT_MESSAGES";
 
+pub static CHAT_MESSAGES_STREAM: &str = "CHAT_MESSAGES_STREAM";
+
+static STREAM_PAYLOAD: &str = "payload";
+
 #[derive(Clone, Debug)]
 pub struct PubSubChatMessages {
     redis: redis::Client,
+    bus: MessageBus,
 }
 
 impl PubSubChatMessages {
-    pub fn new(redis_client: redis::Client) -> PubSubChatMessages {
+    pub fn new(redis_client: redis::Client, bus: MessageBus) -> PubSubChatMessages {
         Self {
             redis: redis_client.clone(),
+            bus,
         }
     }
 }
@@ -29,8 +38,98 @@ impl RedisPubSub for PubSubChatMessages {
 }
 
 impl PubSubChatMessages {
-    pub fn publish_to_channel(&self, chat_message: QueueMessage) -> bool {
-        let chat_message_str = serde_json::to_string(&chat_message).unwrap();
-        self.publish(CHAT_MESSAGES, &chat_message_str)
+    pub fn bus(&self) -> MessageBus {
+        self.bus
+    }
+
+    pub fn instance_channel(instance_id: &str) -> String {
+        format!("{}_{}", CHAT_MESSAGES, instance_id)
+    }
+
+    pub fn instance_stream(instance_id: &str) -> String {
+        format!("{}_{}", CHAT_MESSAGES_STREAM, instance_id)
+    }
+
+    pub fn publish_to_instance(&self, instance_id: &str, payload: &str) -> bool {
+        match self.bus {
+            MessageBus::PubSub => {
+                self.publish(&PubSubChatMessages::instance_channel(instance_id), payload)
+            }
+            MessageBus::Streams => {
+                let stream_id: redis::RedisResult<String> = self.get_connection().xadd_maxlen(
+                    PubSubChatMessages::instance_stream(instance_id),
+                    StreamMaxlen::Approx(STREAM_MAX_LEN),
+                    "*",
+                    &[(STREAM_PAYLOAD, payload)],
+                );
+                match stream_id {
+                    Ok(_) => true,
+                    Err(error) => {
+                        log::error!(
+                            "Failed to add message to stream for instance {}: {}",
+                            instance_id,
+                            error
+                        );
+                        false
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn create_consumer_group(
+        &self,
+        connection: &mut redis::Connection,
+        instance_id: &str,
+    ) -> redis::RedisResult<()> {
+        let created: redis::RedisResult<()> = connection.xgroup_create_mkstream(
+            PubSubChatMessages::instance_stream(instance_id),
+            instance_id,
+            "$",
+        );
+        match created {
+            Err(error) if error.code() == Some("BUSYGROUP") => Ok(()),
+            created => created,
+        }
+    }
+
+    pub fn read_stream(
+        &self,
+        connection: &mut redis::Connection,
+        instance_id: &str,
+        start_id: &str,
+    ) -> redis::RedisResult<Vec<(String, Option<String>)>> {
+        let options = StreamReadOptions::default()
+            .group(instance_id, instance_id)
+            .block(STREAM_BLOCK_MILLIS)
+            .count(STREAM_READ_COUNT);
+        let reply: Option<StreamReadReply> = connection.xread_options(
+            &[PubSubChatMessages::instance_stream(instance_id)],
+            &[start_id],
+            &options,
+        )?;
+
+        Ok(reply
+            .into_iter()
+            .flat_map(|reply| reply.keys)
+            .flat_map(|stream_key| stream_key.ids)
+            .map(|stream_id| {
+                let payload = stream_id.get(STREAM_PAYLOAD);
+                (stream_id.id, payload)
+            })
+            .collect())
+    }
+
+    pub fn ack_stream(
+        &self,
+        connection: &mut redis::Connection,
+        instance_id: &str,
+        stream_id: &str,
+    ) -> redis::RedisResult<()> {
+        connection.xack(
+            PubSubChatMessages::instance_stream(instance_id),
+            instance_id,
+            &[stream_id],
+        )
     }
 }
@@ -0,0 +1,89 @@
+use super::store::RedisUtilityFunc;
+use crate::models::tokens::RefreshTokenRecord;
+use redis::Commands;
+
+pub static REFRESH_TOKEN: &str = "REFRESH_TOKEN_";
+pub static USER_REFRESH_TOKENS: &str = "USER_REFRESH_TOKENS_";
+
+#[derive(Clone, Debug)]
+pub struct RefreshTokens {
+    redis: redis::Client,
+}
+
+impl RefreshTokens {
+    pub fn new(redis_client: redis::Client) -> RefreshTokens {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for RefreshTokens {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RefreshTokens {
+    pub fn create_refresh_token(&self, token: &str, record: &RefreshTokenRecord, ttl_secs: usize) {
+        let user_tokens_key = format!("{}{}", USER_REFRESH_TOKENS, record.username);
+        let _: () = redis::pipe()
+            .atomic()
+            .set_ex(
+                format!("{}{}", REFRESH_TOKEN, token),
+                serde_json::to_string(record).unwrap(),
+                ttl_secs,
+            )
+            .ignore()
+            .sadd(&user_tokens_key, token)
+            .ignore()
+            .expire(&user_tokens_key, ttl_secs)
+            .ignore()
+            .query(&mut self.get_connection())
+            .unwrap();
+    }
+
+    pub fn redeem_refresh_token(&self, token: &str) -> Option<RefreshTokenRecord> {
+        let key = format!("{}{}", REFRESH_TOKEN, token);
+        let (record, _): (Option<String>, usize) = redis::pipe()
+            .atomic()
+            .get(&key)
+            .del(&key)
+            .query(&mut self.get_connection())
+            .unwrap();
+        let record: RefreshTokenRecord = serde_json::from_str(&record?).ok()?;
+        let _: usize = self
+            .get_connection()
+            .srem(format!("{}{}", USER_REFRESH_TOKENS, record.username), token)
+            .unwrap();
+        Some(record)
+    }
+
+    pub fn user_refresh_tokens(&self, username: &str) -> Vec<(String, RefreshTokenRecord)> {
+        let user_tokens_key = format!("{}{}", USER_REFRESH_TOKENS, username);
+        let mut connection = self.get_connection();
+        let tokens: Vec<String> = connection.smembers(&user_tokens_key).unwrap();
+        if tokens.is_empty() {
+            return vec![];
+        }
+        let keys: Vec<String> = tokens
+            .iter()
+            .map(|token| format!("{}{}", REFRESH_TOKEN, token))
+            .collect();
+        let records: Vec<Option<String>> = redis::cmd("MGET")
+            .arg(&keys)
+            .query(&mut connection)
+            .unwrap();
+
+        let mut live = vec![];
+        for (token, record) in tokens.into_iter().zip(records) {
+            match record.and_then(|record| serde_json::from_str(&record).ok()) {
+                Some(record) => live.push((token, record)),
+                None => {
+                    let _: usize = connection.srem(&user_tokens_key, &token).unwrap();
+                }
+            }
+        }
+        live
+    }
+}
@@ -0,0 +1,76 @@
+use super::store::RedisUtilityFunc;
+use redis::Commands;
+
+pub static REVOKED_TOKEN: &str = "REVOKED_TOKEN_";
+pub static REVOKED_TOKEN_FAMILY: &str = "REVOKED_TOKEN_FAMILY_";
+pub static REVOKED_USER_TOKENS: &str = "REVOKED_USER_TOKENS_";
+
+#[derive(Clone, Debug)]
+pub struct RevokedTokens {
+    redis: redis::Client,
+}
+
+impl RevokedTokens {
+    pub fn new(redis_client: redis::Client) -> RevokedTokens {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for RevokedTokens {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RevokedTokens {
+    pub fn revoke_token(&self, jti: &str, ttl_secs: usize) {
+        let _: () = self
+            .get_connection()
+            .set_ex(format!("{}{}", REVOKED_TOKEN, jti), 1, ttl_secs.max(1))
+            .unwrap();
+    }
+
+    pub fn token_revoked(&self, jti: &str) -> bool {
+        self.get_connection()
+            .exists(format!("{}{}", REVOKED_TOKEN, jti))
+            .unwrap()
+    }
+
+    pub fn revoke_family(&self, family: &str, ttl_secs: usize) {
+        let _: () = self
+            .get_connection()
+            .set_ex(
+                format!("{}{}", REVOKED_TOKEN_FAMILY, family),
+                1,
+                ttl_secs.max(1),
+            )
+            .unwrap();
+    }
+
+    pub fn family_revoked(&self, family: &str) -> bool {
+        self.get_connection()
+            .exists(format!("{}{}", REVOKED_TOKEN_FAMILY, family))
+            .unwrap()
+    }
+
+    pub fn revoke_user_tokens(&self, username: &str, issued_before: u64, ttl_secs: usize) {
+        let _: () = self
+            .get_connection()
+            .set_ex(
+                format!("{}{}", REVOKED_USER_TOKENS, username),
+                issued_before,
+                ttl_secs.max(1),
+            )
+            .unwrap();
+    }
+
+    pub fn user_tokens_revoked(&self, username: &str, issued_at: u64) -> bool {
+        let issued_before: Option<u64> = self
+            .get_connection()
+            .get(format!("{}{}", REVOKED_USER_TOKENS, username))
+            .unwrap();
+        issued_before.is_some_and(|issued_before| issued_at < issued_before)
+    }
+}
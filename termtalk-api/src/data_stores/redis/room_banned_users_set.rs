@@ -0,0 +1,59 @@
+use super::store::{RedisSet, RedisSetFns, RedisUtilityFunc};
+
+pub static ROOM_BANNED_USERS_SET: &str = "_ROOM_BANNED_USERS_SET";
+
+#[derive(Clone, Debug)]
+pub struct RoomBannedUsersSet {
+    redis: redis::Client,
+}
+
+impl RoomBannedUsersSet {
+    pub fn new(redis_client: redis::Client) -> RoomBannedUsersSet {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for RoomBannedUsersSet {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RedisSet for RoomBannedUsersSet {
+    fn set_name() -> String {
+        ROOM_BANNED_USERS_SET.to_owned()
+    }
+}
+
+impl RoomBannedUsersSet {
+    pub fn add_banned_user(&self, room: &str, user: &str) -> bool {
+        self.sadd(&format!("{}{}", room, ROOM_BANNED_USERS_SET), user)
+    }
+
+    pub fn remove_banned_user(&self, room: &str, user: &str) -> bool {
+        self.srem(&format!("{}{}", room, ROOM_BANNED_USERS_SET), user)
+    }
+
+    pub fn user_banned(&self, room: &str, user: &str) -> bool {
+        self.sismember(&format!("{}{}", room, ROOM_BANNED_USERS_SET), user)
+    }
+
+    pub fn list_banned_users(&self, room: &str) -> Vec<String> {
+        self.smembers(&format!("{}{}", room, ROOM_BANNED_USERS_SET))
+    }
+
+    pub fn rename_room_set(&self, room: &str, new_room: &str) {
+        for user in self.list_banned_users(room) {
+            self.add_banned_user(new_room, &user);
+            self.remove_banned_user(room, &user);
+        }
+    }
+
+    pub fn clear_room_set(&self, room: &str) {
+        for user in self.list_banned_users(room) {
+            self.remove_banned_user(room, &user);
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use super::store::RedisUtilityFunc;
+use redis::Commands;
+
+pub static ROOM_INVITE: &str = "ROOM_INVITE_";
+
+static REDEEM_INVITE_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+#[derive(Clone, Debug)]
+pub struct RoomInvites {
+    redis: redis::Client,
+}
+
+impl RoomInvites {
+    pub fn new(redis_client: redis::Client) -> RoomInvites {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for RoomInvites {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RoomInvites {
+    pub fn create_invite(&self, code: &str, room: &str, ttl_secs: usize) {
+        let _: () = self
+            .get_connection()
+            .set_ex(format!("{}{}", ROOM_INVITE, code), room, ttl_secs)
+            .unwrap();
+    }
+
+    pub fn redeem_invite(&self, code: &str, room: &str) -> bool {
+        let redeemed: usize = redis::Script::new(REDEEM_INVITE_SCRIPT)
+            .key(format!("{}{}", ROOM_INVITE, code))
+            .arg(room)
+            .invoke(&mut self.get_connection())
+            .unwrap();
+        redeemed == 1
+    }
+}
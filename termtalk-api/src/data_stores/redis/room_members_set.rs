@@ -0,0 +1,59 @@
+use super::store::{RedisSet, RedisSetFns, RedisUtilityFunc};
+
+pub static ROOM_MEMBERS_SET: &str = "_ROOM_MEMBERS_SET";
+
+#[derive(Clone, Debug)]
+pub struct RoomMembersSet {
+    redis: redis::Client,
+}
+
+impl RoomMembersSet {
+    pub fn new(redis_client: redis::Client) -> RoomMembersSet {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for RoomMembersSet {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RedisSet for RoomMembersSet {
+    fn set_name() -> String {
+        ROOM_MEMBERS_SET.to_owned()
+    }
+}
+
+impl RoomMembersSet {
+    pub fn add_member(&self, room: &str, user: &str) -> bool {
+        self.sadd(&format!("{}{}", room, ROOM_MEMBERS_SET), user)
+    }
+
+    pub fn remove_member(&self, room: &str, user: &str) -> bool {
+        self.srem(&format!("{}{}", room, ROOM_MEMBERS_SET), user)
+    }
+
+    pub fn is_member(&self, room: &str, user: &str) -> bool {
+        self.sismember(&format!("{}{}", room, ROOM_MEMBERS_SET), user)
+    }
+
+    pub fn list_members(&self, room: &str) -> Vec<String> {
+        self.smembers(&format!("{}{}", room, ROOM_MEMBERS_SET))
+    }
+
+    pub fn rename_room_set(&self, room: &str, new_room: &str) {
+        for user in self.list_members(room) {
+            self.add_member(new_room, &user);
+            self.remove_member(room, &user);
+        }
+    }
+
+    pub fn clear_room_set(&self, room: &str) {
+        for user in self.list_members(room) {
+            self.remove_member(room, &user);
+        }
+    }
+}
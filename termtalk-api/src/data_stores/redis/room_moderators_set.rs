@@ -0,0 +1,59 @@
+use super::store::{RedisSet, RedisSetFns, RedisUtilityFunc};
+
+pub static ROOM_MODERATORS_SET: &str = "_ROOM_MODERATORS_SET";
+
+#[derive(Clone, Debug)]
+pub struct RoomModeratorsSet {
+    redis: redis::Client,
+}
+
+impl RoomModeratorsSet {
+    pub fn new(redis_client: redis::Client) -> RoomModeratorsSet {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for RoomModeratorsSet {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RedisSet for RoomModeratorsSet {
+    fn set_name() -> String {
+        ROOM_MODERATORS_SET.to_owned()
+    }
+}
+
+impl RoomModeratorsSet {
+    pub fn add_moderator(&self, room: &str, user: &str) -> bool {
+        self.sadd(&format!("{}{}", room, ROOM_MODERATORS_SET), user)
+    }
+
+    pub fn remove_moderator(&self, room: &str, user: &str) -> bool {
+        self.srem(&format!("{}{}", room, ROOM_MODERATORS_SET), user)
+    }
+
+    pub fn is_moderator(&self, room: &str, user: &str) -> bool {
+        self.sismember(&format!("{}{}", room, ROOM_MODERATORS_SET), user)
+    }
+
+    pub fn list_moderators(&self, room: &str) -> Vec<String> {
+        self.smembers(&format!("{}{}", room, ROOM_MODERATORS_SET))
+    }
+
+    pub fn rename_room_set(&self, room: &str, new_room: &str) {
+        for user in self.list_moderators(room) {
+            self.add_moderator(new_room, &user);
+            self.remove_moderator(room, &user);
+        }
+    }
+
+    pub fn clear_room_set(&self, room: &str) {
+        for user in self.list_moderators(room) {
+            self.remove_moderator(room, &user);
+        }
+    }
+}
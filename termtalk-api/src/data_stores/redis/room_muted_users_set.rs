@@ -0,0 +1,59 @@
+use super::store::{RedisSet, RedisSetFns, RedisUtilityFunc};
+
+pub static ROOM_MUTED_USERS_SET: &str = "_ROOM_MUTED_USERS_SET";
+
+#[derive(Clone, Debug)]
+pub struct RoomMutedUsersSet {
+    redis: redis::Client,
+}
+
+impl RoomMutedUsersSet {
+    pub fn new(redis_client: redis::Client) -> RoomMutedUsersSet {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for RoomMutedUsersSet {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RedisSet for RoomMutedUsersSet {
+    fn set_name() -> String {
+        ROOM_MUTED_USERS_SET.to_owned()
+    }
+}
+
+impl RoomMutedUsersSet {
+    pub fn add_muted_user(&self, room: &str, user: &str) -> bool {
+        self.sadd(&format!("{}{}", room, ROOM_MUTED_USERS_SET), user)
+    }
+
+    pub fn remove_muted_user(&self, room: &str, user: &str) -> bool {
+        self.srem(&format!("{}{}", room, ROOM_MUTED_USERS_SET), user)
+    }
+
+    pub fn user_muted(&self, room: &str, user: &str) -> bool {
+        self.sismember(&format!("{}{}", room, ROOM_MUTED_USERS_SET), user)
+    }
+
+    pub fn list_muted_users(&self, room: &str) -> Vec<String> {
+        self.smembers(&format!("{}{}", room, ROOM_MUTED_USERS_SET))
+    }
+
+    pub fn rename_room_set(&self, room: &str, new_room: &str) {
+        for user in self.list_muted_users(room) {
+            self.add_muted_user(new_room, &user);
+            self.remove_muted_user(room, &user);
+        }
+    }
+
+    pub fn clear_room_set(&self, room: &str) {
+        for user in self.list_muted_users(room) {
+            self.remove_muted_user(room, &user);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use super::store::{RedisHashMap, RedisHashMapFns, RedisUtilityFunc};
+
+pub static ROOM_OWNERS_HASH_MAP: &str = "ROOM_OWNERS_HASH_MAP";
+
+#[derive(Clone, Debug)]
+pub struct RoomOwnersHashMap {
+    redis: redis::Client,
+}
+
+impl RoomOwnersHashMap {
+    pub fn new(redis_client: redis::Client) -> RoomOwnersHashMap {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for RoomOwnersHashMap {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RedisHashMap for RoomOwnersHashMap {
+    fn hash_map_name() -> String {
+        ROOM_OWNERS_HASH_MAP.to_owned()
+    }
+}
+
+impl RoomOwnersHashMap {
+    pub fn get_room_owner(&self, room_name: &str) -> Option<String> {
+        self.hget(ROOM_OWNERS_HASH_MAP, room_name)
+    }
+
+    pub fn set_room_owner(&self, room_name: &str, username: &str) -> Option<String> {
+        self.hset(ROOM_OWNERS_HASH_MAP, room_name, username)
+    }
+
+    pub fn remove_room_owner(&self, room_name: &str) -> bool {
+        self.hdel(ROOM_OWNERS_HASH_MAP, room_name)
+    }
+}
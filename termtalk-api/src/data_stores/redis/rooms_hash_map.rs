@@ -28,12 +28,20 @@ impl RedisHashMap for RoomsHashMap {
 }
 
 impl RoomsHashMap {
-    pub fn _get_room_guid_by_name(&self, room_name: &str) -> Option<String> {
+    pub fn get_room_guid_by_name(&self, room_name: &str) -> Option<String> {
         self.hget(ROOMS_HASH_MAP, room_name)
     }
 
-    pub fn _set_room_guid_by_name(&self, room_guid: &str, room_name: &str) -> Option<String> {
-        self.hset(ROOMS_HASH_MAP, room_guid, room_name)
+    pub fn set_room_guid_by_name(&self, room_name: &str, room_guid: &str) -> Option<String> {
+        self.hset(ROOMS_HASH_MAP, room_name, room_guid)
+    }
+
+    pub fn set_room_guid_by_name_if_absent(&self, room_name: &str, room_guid: &str) -> bool {
+        self.hsetnx(ROOMS_HASH_MAP, room_name, room_guid)
+    }
+
+    pub fn remove_room(&self, room_name: &str) -> bool {
+        self.hdel(ROOMS_HASH_MAP, room_name)
     }
 
     pub fn list_rooms(&self) -> Vec<String> {
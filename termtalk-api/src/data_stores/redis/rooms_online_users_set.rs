@@ -36,7 +36,22 @@ impl RoomsOnlineUsersSet {
         self.srem(&format!("{}{}", room, ROOMS_ONLINE_USERS_SET), user)
     }
 
+    pub fn user_in_room(&self, room: &str, user: &str) -> bool {
+        self.sismember(&format!("{}{}", room, ROOMS_ONLINE_USERS_SET), user)
+    }
+
     pub fn list_users_in_room(&self, room: &str) -> Vec<String> {
         self.smembers(&format!("{}{}", room, ROOMS_ONLINE_USERS_SET))
     }
+
+    pub fn rename_room_set(&self, room: &str, new_room: &str) {
+        for user in self.list_users_in_room(room) {
+            self.add_user_to_room_set(new_room, &user);
+            self.remove_user_from_room_set(room, &user);
+        }
+    }
+
+    pub fn count_users_in_room(&self, room: &str) -> usize {
+        self.scard(&format!("{}{}", room, ROOMS_ONLINE_USERS_SET))
+    }
 }
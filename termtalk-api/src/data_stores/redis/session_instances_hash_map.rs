@@ -0,0 +1,52 @@
+use super::store::{RedisHashMap, RedisHashMapFns, RedisUtilityFunc};
+use std::collections::HashSet;
+
+pub static SESSION_INSTANCES_HASH_MAP: &str = "SESSION_INSTANCES_";
+
+#[derive(Clone, Debug)]
+pub struct SessionInstancesHashMap {
+    redis: redis::Client,
+}
+
+impl SessionInstancesHashMap {
+    pub fn new(redis_client: redis::Client) -> SessionInstancesHashMap {
+        Self {
+            redis: redis_client.clone(),
+        }
+    }
+}
+
+impl RedisUtilityFunc for SessionInstancesHashMap {
+    fn get_redis_attr(&self) -> redis::Client {
+        self.redis.clone()
+    }
+}
+
+impl RedisHashMap for SessionInstancesHashMap {
+    fn hash_map_name() -> String {
+        SESSION_INSTANCES_HASH_MAP.to_owned()
+    }
+}
+
+impl SessionInstancesHashMap {
+    pub fn set_session_instance(&self, username: &str, connection_id: &str, instance_id: &str) {
+        self.hset(
+            &format!("{}{}", SESSION_INSTANCES_HASH_MAP, username),
+            connection_id,
+            instance_id,
+        );
+    }
+
+    pub fn remove_session_instance(&self, username: &str, connection_id: &str) -> bool {
+        self.hdel(
+            &format!("{}{}", SESSION_INSTANCES_HASH_MAP, username),
+            connection_id,
+        )
+    }
+
+    pub fn user_instances(&self, username: &str) -> HashSet<String> {
+        self.hvals(&format!("{}{}", SESSION_INSTANCES_HASH_MAP, username))
+            .into_iter()
+            .collect()
+    }
+}
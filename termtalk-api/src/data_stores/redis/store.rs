@@ -1,7 +1,16 @@
 use super::{
-    publish_chat_messages::PubSubChatMessages, rooms_hash_map::RoomsHashMap,
-    rooms_online_users_set::RoomsOnlineUsersSet, users_online_set::UsersOnlineSet,
+    archived_rooms_set::ArchivedRoomsSet, direct_unread_hash_map::DirectUnreadHashMap,
+    offline_messages_list::OfflineMessagesList, presence_leases::PresenceLeases,
+    private_rooms_set::PrivateRoomsSet, publish_chat_messages::PubSubChatMessages,
+    refresh_tokens::RefreshTokens, revoked_tokens::RevokedTokens,
+    room_banned_users_set::RoomBannedUsersSet, room_invites::RoomInvites,
+    room_members_set::RoomMembersSet, room_moderators_set::RoomModeratorsSet,
+    room_muted_users_set::RoomMutedUsersSet, room_owners_hash_map::RoomOwnersHashMap,
+    rooms_hash_map::RoomsHashMap, rooms_online_users_set::RoomsOnlineUsersSet,
+    session_instances_hash_map::SessionInstancesHashMap, users_online_set::UsersOnlineSet,
 };
+use crate::models::message_bus::MessageBus;
+use crate::models::tokens::{RefreshTokenRecord, RefreshTokenStore};
 use redis::Commands;
 
 #[derive(Clone, Debug)]
@@ -11,20 +20,66 @@ pub struct RedisStore {
     pub users_online_set: UsersOnlineSet,
     pub publish_chat_messages: PubSubChatMessages,
     pub rooms_online_users_set: RoomsOnlineUsersSet,
+    pub archived_rooms_set: ArchivedRoomsSet,
+    pub room_owners_hash_map: RoomOwnersHashMap,
+    pub room_moderators_set: RoomModeratorsSet,
+    pub room_banned_users_set: RoomBannedUsersSet,
+    pub room_muted_users_set: RoomMutedUsersSet,
+    pub private_rooms_set: PrivateRoomsSet,
+    pub room_members_set: RoomMembersSet,
+    pub room_invites: RoomInvites,
+    pub direct_unread_hash_map: DirectUnreadHashMap,
+    pub offline_messages_list: OfflineMessagesList,
+    pub presence_leases: PresenceLeases,
+    pub session_instances_hash_map: SessionInstancesHashMap,
+    pub refresh_tokens: RefreshTokens,
+    pub revoked_tokens: RevokedTokens,
 }
 
 impl RedisStore {
-    pub fn new(redis_client: redis::Client) -> RedisStore {
+    pub fn new(redis_client: redis::Client, message_bus: MessageBus) -> RedisStore {
         RedisStore {
             redis: redis_client.clone(),
             rooms_hash_map: RoomsHashMap::new(redis_client.clone()),
             users_online_set: UsersOnlineSet::new(redis_client.clone()),
-            publish_chat_messages: PubSubChatMessages::new(redis_client.clone()),
+            publish_chat_messages: PubSubChatMessages::new(redis_client.clone(), message_bus),
             rooms_online_users_set: RoomsOnlineUsersSet::new(redis_client.clone()),
+            archived_rooms_set: ArchivedRoomsSet::new(redis_client.clone()),
+            room_owners_hash_map: RoomOwnersHashMap::new(redis_client.clone()),
+            room_moderators_set: RoomModeratorsSet::new(redis_client.clone()),
+            room_banned_users_set: RoomBannedUsersSet::new(redis_client.clone()),
+            room_muted_users_set: RoomMutedUsersSet::new(redis_client.clone()),
+            private_rooms_set: PrivateRoomsSet::new(redis_client.clone()),
+            room_members_set: RoomMembersSet::new(redis_client.clone()),
+            room_invites: RoomInvites::new(redis_client.clone()),
+            direct_unread_hash_map: DirectUnreadHashMap::new(redis_client.clone()),
+            offline_messages_list: OfflineMessagesList::new(redis_client.clone()),
+            presence_leases: PresenceLeases::new(redis_client.clone()),
+            session_instances_hash_map: SessionInstancesHashMap::new(redis_client.clone()),
+            refresh_tokens: RefreshTokens::new(redis_client.clone()),
+            revoked_tokens: RevokedTokens::new(redis_client.clone()),
         }
     }
 }
 
+impl RefreshTokenStore for RedisStore {
+    fn redeem_refresh_token(&self, token: &str) -> Option<RefreshTokenRecord> {
+        self.refresh_tokens.redeem_refresh_token(token)
+    }
+
+    fn user_refresh_tokens(&self, username: &str) -> Vec<(String, RefreshTokenRecord)> {
+        self.refresh_tokens.user_refresh_tokens(username)
+    }
+
+    fn token_revoked(&self, jti: &str) -> bool {
+        self.revoked_tokens.token_revoked(jti)
+    }
+
+    fn family_revoked(&self, family: &str) -> bool {
+        self.revoked_tokens.family_revoked(family)
+    }
+}
+
 pub trait RedisUtilityFunc {
     fn get_redis_attr(&self) -> redis::Client;
     fn get_connection(&self) -> redis::Connection {
@@ -39,6 +94,9 @@ pub trait RedisHashMap {
 pub trait RedisHashMapFns {
     fn hget(&self, hash_map_name: &str, key: &str) -> Option<String>;
     fn hset(&self, hash_map_name: &str, key: &str, val: &str) -> Option<String>;
+    fn hsetnx(&self, hash_map_name: &str, key: &str, val: &str) -> bool;
+    fn hdel(&self, hash_map_name: &str, key: &str) -> bool;
+    fn hincr(&self, hash_map_name: &str, key: &str, delta: i64) -> i64;
     fn hkeys(&self, hash_map_name: &str) -> Vec<String>;
     fn hvals(&self, hash_map_name: &str) -> Vec<String>;
 }
@@ -57,6 +115,22 @@ where
             .unwrap()
     }
 
+    fn hsetnx(&self, hash_map_name: &str, key: &str, val: &str) -> bool {
+        self.get_connection()
+            .hset_nx(hash_map_name, key, val)
+            .unwrap()
+    }
+
+    fn hdel(&self, hash_map_name: &str, key: &str) -> bool {
+        self.get_connection().hdel(hash_map_name, key).unwrap()
+    }
+
+    fn hincr(&self, hash_map_name: &str, key: &str, delta: i64) -> i64 {
+        self.get_connection()
+            .hincr(hash_map_name, key, delta)
+            .unwrap()
+    }
+
     fn hkeys(&self, hash_map_name: &str) -> Vec<String> {
         self.get_connection().hkeys(hash_map_name).unwrap()
     }
@@ -75,6 +149,7 @@ pub trait RedisSetFns {
     fn srem(&self, set_name: &str, val: &str) -> bool;
     fn sismember(&self, set_name: &str, val: &str) -> bool;
     fn smembers(&self, set_name: &str) -> Vec<String>;
+    fn scard(&self, set_name: &str) -> usize;
 }
 
 impl<T> RedisSetFns for T
@@ -96,6 +171,43 @@ where
     fn smembers(&self, set_name: &str) -> Vec<String> {
         self.get_connection().smembers(set_name).unwrap()
     }
+
+    fn scard(&self, set_name: &str) -> usize {
+        self.get_connection().scard(set_name).unwrap()
+    }
+}
+
+pub trait RedisList {
+    fn list_name() -> String;
+}
+
+pub trait RedisListFns {
+    fn rpush(&self, list_name: &str, val: &str) -> usize;
+    fn ltrim(&self, list_name: &str, start: isize, stop: isize);
+    fn drain(&self, list_name: &str) -> Vec<String>;
+}
+
+impl<T> RedisListFns for T
+where
+    T: RedisUtilityFunc + RedisList,
+{
+    fn rpush(&self, list_name: &str, val: &str) -> usize {
+        self.get_connection().rpush(list_name, val).unwrap()
+    }
+
+    fn ltrim(&self, list_name: &str, start: isize, stop: isize) {
+        let _: () = self.get_connection().ltrim(list_name, start, stop).unwrap();
+    }
+
+    fn drain(&self, list_name: &str) -> Vec<String> {
+        let (vals, _): (Vec<String>, usize) = redis::pipe()
+            .atomic()
+            .lrange(list_name, 0, -1)
+            .del(list_name)
+            .query(&mut self.get_connection())
+            .unwrap();
+        vals
+    }
 }
 
 pub trait RedisPubSub {
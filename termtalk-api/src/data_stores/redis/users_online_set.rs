@@ -36,10 +36,6 @@ impl UsersOnlineSet {
         self.srem(USERS_ONLINE, val)
     }
 
-    pub fn user_online(&self, val: &str) -> bool {
-        self.sismember(USERS_ONLINE, &val)
-    }
-
     pub fn users_online(&self) -> Vec<String> {
         self.smembers(USERS_ONLINE)
     }
@@ -0,0 +1,152 @@
+use super::keys::{JwtAlgorithm, JwtKey};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::{error::Error, fmt, str::FromStr};
+
+#[derive(Debug, Clone)]
+pub struct JwtKeyring {
+    active_kid: String,
+    keys: HashMap<String, JwtKey>,
+}
+
+impl JwtKeyring {
+    pub fn active(&self) -> (&str, &JwtKey) {
+        (&self.active_kid, &self.keys[&self.active_kid])
+    }
+
+    pub fn get(&self, kid: &str) -> Option<&JwtKey> {
+        self.keys.get(kid)
+    }
+
+    pub fn jwks(&self) -> Value {
+        let mut kids: Vec<&String> = self.keys.keys().collect();
+        kids.sort();
+        let keys: Vec<Value> = kids
+            .into_iter()
+            .filter_map(|kid| self.keys[kid].jwk(kid))
+            .collect();
+        json!({ "keys": keys })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidJwtKeyring(pub String);
+
+impl Error for InvalidJwtKeyring {}
+
+impl fmt::Display for InvalidJwtKeyring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}. Keys are listed as kid:secret, kid:EdDSA:key.pem or kid:RS256:key.pem entries separated by commas, and the first one signs new tokens",
+            self.0
+        )
+    }
+}
+
+impl FromStr for JwtKeyring {
+    type Err = InvalidJwtKeyring;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut active_kid = None;
+        let mut keys = HashMap::new();
+        for entry in s.split(',').map(|entry| entry.trim()) {
+            let (kid, secret) = match entry.split_once(':') {
+                Some((kid, secret)) if !kid.trim().is_empty() && !secret.is_empty() => {
+                    (kid.trim(), secret)
+                }
+                _ => return Err(InvalidJwtKeyring(format!("'{}' is not a key", entry))),
+            };
+            let key = match secret.split_once(':') {
+                Some((algorithm, pem_path)) if algorithm == "EdDSA" || algorithm == "RS256" => {
+                    JwtKey::from_pem(algorithm.parse::<JwtAlgorithm>().unwrap(), pem_path)
+                        .map_err(InvalidJwtKeyring)?
+                }
+                _ => JwtKey::Hmac(secret.as_bytes().to_vec()),
+            };
+            if active_kid.is_none() && !key.can_sign() {
+                return Err(InvalidJwtKeyring(format!(
+                    "Key id {} signs new tokens but has no private key",
+                    kid
+                )));
+            }
+            if keys.insert(kid.to_owned(), key).is_some() {
+                return Err(InvalidJwtKeyring(format!("Key id {} is repeated", kid)));
+            }
+            active_kid.get_or_insert_with(|| kid.to_owned());
+        }
+
+        Ok(JwtKeyring {
+            active_kid: active_kid.unwrap(),
+            keys,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JwtConfig {
+    pub issuer: String,
+    pub audience: String,
+    pub lifetime_secs: u64,
+    pub refresh_lifetime_secs: u64,
+    pub keyring: JwtKeyring,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jwt::fixtures::PemFixtures;
+
+    #[test]
+    fn test_parse_keyring() {
+        let keyring: JwtKeyring = "2026-10:new secret, 2026-04:old:secret".parse().unwrap();
+        assert!(matches!(
+            keyring.active(),
+            ("2026-10", JwtKey::Hmac(secret)) if secret == b"new secret"
+        ));
+        assert!(matches!(
+            keyring.get("2026-04"),
+            Some(JwtKey::Hmac(secret)) if secret == b"old:secret"
+        ));
+        assert!(keyring.get("2025-10").is_none());
+        assert_eq!(json!({ "keys": [] }), keyring.jwks());
+
+        assert!("".parse::<JwtKeyring>().is_err());
+        assert!("no-secret:".parse::<JwtKeyring>().is_err());
+        assert!("a:one,a:two".parse::<JwtKeyring>().is_err());
+    }
+
+    #[test]
+    fn test_parse_asymmetric_keyring() {
+        let pems = PemFixtures::new("jwks");
+
+        let keyring: JwtKeyring = format!(
+            "ed:EdDSA:{},rsa:RS256:{},hmac:secret",
+            pems.ed25519_path.display(),
+            pems.rsa_public_path.display()
+        )
+        .parse()
+        .unwrap();
+        assert_eq!(JwtAlgorithm::EdDsa, keyring.active().1.algorithm());
+        assert_eq!(JwtAlgorithm::Rs256, keyring.get("rsa").unwrap().algorithm());
+
+        let jwks = keyring.jwks();
+        let keys = jwks["keys"].as_array().unwrap();
+        assert_eq!(2, keys.len());
+        assert_eq!("OKP", keys[0]["kty"]);
+        assert_eq!("ed", keys[0]["kid"]);
+        assert_eq!(
+            base64_url::encode(&pems.ed25519.raw_public_key().unwrap()),
+            keys[0]["x"]
+        );
+        assert_eq!("RSA", keys[1]["kty"]);
+        assert_eq!("AQAB", keys[1]["e"]);
+
+        assert!(format!("rsa:RS256:{}", pems.rsa_public_path.display())
+            .parse::<JwtKeyring>()
+            .is_err());
+        assert!(format!("ed:RS256:{}", pems.ed25519_path.display())
+            .parse::<JwtKeyring>()
+            .is_err());
+    }
+}
@@ -0,0 +1,43 @@
+use openssl::pkey::{PKey, Private};
+use openssl::rsa::Rsa;
+use std::fs;
+use std::path::PathBuf;
+
+pub struct PemFixtures {
+    dir: PathBuf,
+    pub ed25519: PKey<Private>,
+    pub ed25519_path: PathBuf,
+    pub rsa_path: PathBuf,
+    pub rsa_public_path: PathBuf,
+}
+
+impl PemFixtures {
+    pub fn new(name: &str) -> PemFixtures {
+        let dir = std::env::temp_dir().join(format!("termtalk-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let ed25519 = PKey::generate_ed25519().unwrap();
+        let ed25519_path = dir.join("ed25519.pem");
+        fs::write(&ed25519_path, ed25519.private_key_to_pem_pkcs8().unwrap()).unwrap();
+
+        let rsa = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let rsa_path = dir.join("rsa.pem");
+        fs::write(&rsa_path, rsa.private_key_to_pem_pkcs8().unwrap()).unwrap();
+        let rsa_public_path = dir.join("rsa.pub.pem");
+        fs::write(&rsa_public_path, rsa.public_key_to_pem().unwrap()).unwrap();
+
+        PemFixtures {
+            dir,
+            ed25519,
+            ed25519_path,
+            rsa_path,
+            rsa_public_path,
+        }
+    }
+}
+
+impl Drop for PemFixtures {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
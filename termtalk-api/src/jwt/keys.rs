@@ -0,0 +1,199 @@
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha2::Sha256;
+use crypto::util::fixed_time_eq;
+use openssl::hash::MessageDigest;
+use openssl::pkey::{Id, PKey, Private, Public};
+use openssl::sign::{Signer, Verifier};
+use serde_json::{json, Value};
+use std::{fmt, fs, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JwtAlgorithm {
+    Hs256,
+    EdDsa,
+    Rs256,
+}
+
+impl JwtAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JwtAlgorithm::Hs256 => "HS256",
+            JwtAlgorithm::EdDsa => "EdDSA",
+            JwtAlgorithm::Rs256 => "RS256",
+        }
+    }
+}
+
+impl fmt::Display for JwtAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for JwtAlgorithm {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "HS256" => Ok(JwtAlgorithm::Hs256),
+            "EdDSA" => Ok(JwtAlgorithm::EdDsa),
+            "RS256" => Ok(JwtAlgorithm::Rs256),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum JwtKey {
+    Hmac(Vec<u8>),
+    Asymmetric {
+        algorithm: JwtAlgorithm,
+        public_key: PKey<Public>,
+        private_key: Option<PKey<Private>>,
+    },
+}
+
+impl JwtKey {
+    pub fn from_pem(algorithm: JwtAlgorithm, pem_path: &str) -> Result<JwtKey, String> {
+        let pem = fs::read(pem_path).map_err(|e| format!("Could not read {}: {}", pem_path, e))?;
+        let (public_key, private_key) = match PKey::private_key_from_pem(&pem) {
+            Ok(private_key) => {
+                let public_key = private_key
+                    .public_key_to_pem()
+                    .and_then(|public_pem| PKey::public_key_from_pem(&public_pem))
+                    .map_err(|e| {
+                        format!("Could not read the public half of {}: {}", pem_path, e)
+                    })?;
+                (public_key, Some(private_key))
+            }
+            Err(_) => match PKey::public_key_from_pem(&pem) {
+                Ok(public_key) => (public_key, None),
+                Err(e) => return Err(format!("{} is not a PEM key: {}", pem_path, e)),
+            },
+        };
+
+        let expected_id = match algorithm {
+            JwtAlgorithm::EdDsa => Id::ED25519,
+            JwtAlgorithm::Rs256 => Id::RSA,
+            JwtAlgorithm::Hs256 => return Err(String::from("HS256 keys are plain secrets")),
+        };
+        if public_key.id() != expected_id {
+            return Err(format!("{} is not a {} key", pem_path, algorithm));
+        }
+
+        Ok(JwtKey::Asymmetric {
+            algorithm,
+            public_key,
+            private_key,
+        })
+    }
+
+    pub fn algorithm(&self) -> JwtAlgorithm {
+        match self {
+            JwtKey::Hmac(_) => JwtAlgorithm::Hs256,
+            JwtKey::Asymmetric { algorithm, .. } => *algorithm,
+        }
+    }
+
+    pub fn can_sign(&self) -> bool {
+        match self {
+            JwtKey::Hmac(_) => true,
+            JwtKey::Asymmetric { private_key, .. } => private_key.is_some(),
+        }
+    }
+
+    pub fn sign(&self, input: &[u8]) -> String {
+        let signature = match self {
+            JwtKey::Hmac(secret_key) => hmacsha256(secret_key, input),
+            JwtKey::Asymmetric {
+                algorithm,
+                private_key,
+                ..
+            } => {
+                let private_key = private_key.as_ref().unwrap();
+                match algorithm {
+                    JwtAlgorithm::Rs256 => {
+                        let mut signer = Signer::new(MessageDigest::sha256(), private_key).unwrap();
+                        signer.update(input).unwrap();
+                        signer.sign_to_vec().unwrap()
+                    }
+                    _ => Signer::new_without_digest(private_key)
+                        .and_then(|mut signer| signer.sign_oneshot_to_vec(input))
+                        .unwrap(),
+                }
+            }
+        };
+        base64_url::encode(&signature)
+            .trim_end_matches("=")
+            .to_string()
+    }
+
+    pub fn verify(&self, input: &[u8], signature: &str) -> bool {
+        let signature = match base64_url::decode(signature) {
+            Ok(val) => val,
+            Err(_) => return false,
+        };
+        match self {
+            JwtKey::Hmac(secret_key) => fixed_time_eq(&hmacsha256(secret_key, input), &signature),
+            JwtKey::Asymmetric {
+                algorithm,
+                public_key,
+                ..
+            } => {
+                let verified = match algorithm {
+                    JwtAlgorithm::Rs256 => Verifier::new(MessageDigest::sha256(), public_key)
+                        .and_then(|mut verifier| {
+                            verifier.update(input)?;
+                            verifier.verify(&signature)
+                        }),
+                    _ => Verifier::new_without_digest(public_key)
+                        .and_then(|mut verifier| verifier.verify_oneshot(&signature, input)),
+                };
+                verified.unwrap_or(false)
+            }
+        }
+    }
+
+    pub fn jwk(&self, kid: &str) -> Option<Value> {
+        let (algorithm, public_key) = match self {
+            JwtKey::Hmac(_) => return None,
+            JwtKey::Asymmetric {
+                algorithm,
+                public_key,
+                ..
+            } => (algorithm, public_key),
+        };
+        match algorithm {
+            JwtAlgorithm::EdDsa => Some(json!({
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "use": "sig",
+                "alg": algorithm.as_str(),
+                "kid": kid,
+                "x": encode_jwk_part(&public_key.raw_public_key().ok()?),
+            })),
+            _ => {
+                let rsa = public_key.rsa().ok()?;
+                Some(json!({
+                    "kty": "RSA",
+                    "use": "sig",
+                    "alg": algorithm.as_str(),
+                    "kid": kid,
+                    "n": encode_jwk_part(&rsa.n().to_vec()),
+                    "e": encode_jwk_part(&rsa.e().to_vec()),
+                }))
+            }
+        }
+    }
+}
+
+fn hmacsha256(secret_key: &[u8], input: &[u8]) -> Vec<u8> {
+    let mut new_hmac = Hmac::new(Sha256::new(), secret_key);
+    new_hmac.input(input);
+    new_hmac.result().code().to_vec()
+}
+
+fn encode_jwk_part(bytes: &[u8]) -> String {
+    base64_url::encode(bytes).trim_end_matches("=").to_string()
+}
@@ -1 +1,5 @@
+pub mod config;
+#[cfg(test)]
+pub mod fixtures;
+pub mod keys;
 pub mod lib;
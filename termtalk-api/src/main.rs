@@ -6,23 +6,29 @@ mod jwt;
 mod models;
 mod routes;
 mod session;
+mod subscriber;
+mod utils;
 
 use actix::Actor;
 use actix_web::{middleware, web, App, HttpServer};
-use constants::{REDIS_HOST, REDIS_PORT, TERMTALK_API_HOST, TERMTALK_API_PORT};
-use data_stores::{
-    elastic::store::ElasticStore,
-    redis::{publish_chat_messages::CHAT_MESSAGES, store::RedisStore},
+use constants::{
+    DEFAULT_JWT_AUDIENCE, DEFAULT_JWT_ISSUER, DEFAULT_JWT_KID, DEFAULT_JWT_LIFETIME_SECS,
+    DEFAULT_JWT_REFRESH_LIFETIME_SECS, DEFAULT_SCROLLBACK_SIZE, INSTANCE_ID, JWT_AUDIENCE,
+    JWT_ISSUER, JWT_KEYS, JWT_LIFETIME_SECS, JWT_REFRESH_LIFETIME_SECS, MESSAGE_BUS, REDIS_HOST,
+    REDIS_PORT, SCROLLBACK_SIZE, SECRET_KEY, SESSION_POLICY, TERMTALK_API_HOST, TERMTALK_API_PORT,
+};
+use data_stores::{elastic::store::ElasticStore, redis::store::RedisStore};
+use jwt::config::{JwtConfig, JwtKeyring};
+use routes::{
+    archive_room, connect, create_room, create_room_invite, delete_room, healthcheck, jwks, login,
+    logout, logout_everywhere, refresh, register, rename_room, search, set_user_roles,
 };
-use routes::{connect, healthcheck, login, register};
 use std::env;
-use std::sync::Arc;
 use std::thread;
 
-use chat_server::{
-    chat_server::{ChatServer, QueueMessage},
-    handlers::send_client_message::SendClientMessage,
-};
+use chat_server::chat_server::ChatServer;
+use models::{message_bus::MessageBus, sessions::SessionPolicy};
+use uuid::Uuid;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -37,36 +43,79 @@ async fn main() -> std::io::Result<()> {
     .unwrap();
     let elastic_client = elasticsearch::Elasticsearch::default();
 
-    let redis_store: RedisStore = RedisStore::new(redis_client.clone());
+    let message_bus: MessageBus = match env::var(MESSAGE_BUS) {
+        Ok(val) => val.parse::<MessageBus>().unwrap(),
+        Err(_) => MessageBus::default(),
+    };
+
+    let jwt_keys: String = match env::var(JWT_KEYS) {
+        Ok(val) => val,
+        Err(_) => format!("{}:{}", DEFAULT_JWT_KID, env::var(SECRET_KEY).unwrap()),
+    };
+    let jwt_config = JwtConfig {
+        issuer: match env::var(JWT_ISSUER) {
+            Ok(val) => val,
+            Err(_) => DEFAULT_JWT_ISSUER.to_owned(),
+        },
+        audience: match env::var(JWT_AUDIENCE) {
+            Ok(val) => val,
+            Err(_) => DEFAULT_JWT_AUDIENCE.to_owned(),
+        },
+        lifetime_secs: match env::var(JWT_LIFETIME_SECS) {
+            Ok(val) => val.parse::<u64>().unwrap(),
+            Err(_) => DEFAULT_JWT_LIFETIME_SECS,
+        },
+        refresh_lifetime_secs: match env::var(JWT_REFRESH_LIFETIME_SECS) {
+            Ok(val) => val.parse::<u64>().unwrap(),
+            Err(_) => DEFAULT_JWT_REFRESH_LIFETIME_SECS,
+        },
+        keyring: jwt_keys.parse::<JwtKeyring>().unwrap(),
+    };
+
+    let redis_store: RedisStore = RedisStore::new(redis_client.clone(), message_bus);
     let elastic_store: ElasticStore = ElasticStore::new(elastic_client.clone());
 
-    let chat_server: actix::Addr<ChatServer> =
-        ChatServer::new(redis_store.clone(), elastic_store.clone())
-            .await
-            .start();
-
-    let pubsub_chat_server_ = Arc::new(chat_server.clone());
-    let pubsub_redis_store = Arc::new(redis_store.clone());
+    let scrollback_size: usize = match env::var(SCROLLBACK_SIZE) {
+        Ok(val) => match val.parse::<usize>() {
+            Ok(val) => val,
+            Err(_) => {
+                log::warn!(
+                    "{} must be a non-negative integer, got {:?}. Using {} instead",
+                    SCROLLBACK_SIZE,
+                    val,
+                    DEFAULT_SCROLLBACK_SIZE
+                );
+                DEFAULT_SCROLLBACK_SIZE
+            }
+        },
+        Err(_) => DEFAULT_SCROLLBACK_SIZE,
+    };
+
+    let session_policy: SessionPolicy = match env::var(SESSION_POLICY) {
+        Ok(val) => val.parse::<SessionPolicy>().unwrap(),
+        Err(_) => SessionPolicy::default(),
+    };
+
+    let instance_id: String = match env::var(INSTANCE_ID) {
+        Ok(val) => val,
+        Err(_) => Uuid::new_v4().to_string(),
+    };
+    log::info!("Starting instance {}", instance_id);
+
+    let chat_server: actix::Addr<ChatServer> = ChatServer::new(
+        redis_store.clone(),
+        elastic_store.clone(),
+        scrollback_size,
+        session_policy,
+        instance_id.clone(),
+    )
+    .await
+    .start();
 
+    let subscriber_chat_server = chat_server.clone();
+    let subscriber_redis_store = redis_store.clone();
     thread::spawn(move || {
-        let chat_server = Arc::clone(&pubsub_chat_server_);
-        let redis_store = Arc::clone(&pubsub_redis_store);
-        let mut redis_conn = redis_store.redis.get_connection().unwrap();
-        let mut pubsub = redis_conn.as_pubsub();
-        pubsub.subscribe(CHAT_MESSAGES).unwrap();
-
-        loop {
-            let msg: redis::Msg = pubsub.get_message().unwrap();
-            let msg_payload: String = msg.get_payload().unwrap();
-            let msg_deserialized: QueueMessage = serde_json::from_str(&msg_payload).unwrap();
-            chat_server.do_send(SendClientMessage {
-                sender: msg_deserialized.sender.clone(),
-                msg: msg_deserialized.msg.clone(),
-                chat_type: msg_deserialized.chat_type,
-                msg_type: msg_deserialized.msg_type,
-                recipient: msg_deserialized.recipient.clone(),
-            });
-        }
+        subscriber::subscribe(subscriber_chat_server, subscriber_redis_store, instance_id)
     });
 
     let termtalk_api_host = env::var(TERMTALK_API_HOST).unwrap();
@@ -81,12 +130,24 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(redis_store.clone()))
             .app_data(web::Data::new(elastic_store.clone()))
             .app_data(web::Data::new(chat_server.clone()))
+            .app_data(web::Data::new(jwt_config.clone()))
             .wrap(custom_middleware::auth::Authenticate)
             .wrap(middleware::Logger::default())
             .service(healthcheck)
             .service(register)
             .service(login)
+            .service(refresh)
+            .service(logout)
+            .service(logout_everywhere)
+            .service(jwks)
             .service(connect)
+            .service(search)
+            .service(create_room)
+            .service(rename_room)
+            .service(create_room_invite)
+            .service(archive_room)
+            .service(delete_room)
+            .service(set_user_roles)
     })
     .bind((termtalk_api_host, termtalk_api_port))?
     .run()
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use termtalk_proto::direct_chats::DirectChatSummary;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DirectChatDocument {
+    pub name: String,
+    pub participants: Vec<String>,
+    pub created_at: u64,
+    pub last_activity_at: u64,
+}
+
+impl DirectChatDocument {
+    pub fn into_summary(self, username: &str, unread: usize) -> DirectChatSummary {
+        let with = self
+            .participants
+            .iter()
+            .find(|participant| *participant != username)
+            .cloned()
+            .unwrap_or_else(|| username.to_owned());
+
+        DirectChatSummary {
+            name: self.name,
+            with,
+            last_activity_at: self.last_activity_at,
+            unread,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_picks_other_participant() {
+        let direct_chat = DirectChatDocument {
+            name: String::from("alice_bob"),
+            participants: vec![String::from("alice"), String::from("bob")],
+            created_at: 1,
+            last_activity_at: 2,
+        };
+        let summary = direct_chat.into_summary("bob", 3);
+
+        assert_eq!("alice", summary.with);
+        assert_eq!(3, summary.unread);
+        assert_eq!(2, summary.last_activity_at);
+    }
+}
@@ -20,5 +20,7 @@ pub struct TotalHits {
 pub struct DocumentMetadata<T> {
     pub _id: String,
     _index: String,
+    #[serde(default)]
+    pub _score: Option<f64>,
     pub _source: T,
 }
@@ -0,0 +1,51 @@
+use std::{error::Error, fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MessageBus {
+    #[default]
+    PubSub,
+    Streams,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidMessageBus(pub String);
+
+impl Error for InvalidMessageBus {}
+
+impl fmt::Display for InvalidMessageBus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not a message bus. Use pubsub or streams",
+            self.0
+        )
+    }
+}
+
+impl FromStr for MessageBus {
+    type Err = InvalidMessageBus;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['-', '_'], "").as_str() {
+            "pubsub" => Ok(MessageBus::PubSub),
+            "streams" => Ok(MessageBus::Streams),
+            _ => Err(InvalidMessageBus(s.to_owned())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_message_bus() {
+        assert_eq!(Ok(MessageBus::PubSub), "pubsub".parse());
+        assert_eq!(Ok(MessageBus::PubSub), "pub_sub".parse());
+        assert_eq!(Ok(MessageBus::Streams), " Streams ".parse());
+        assert_eq!(
+            Err(InvalidMessageBus(String::from("kafka"))),
+            "kafka".parse::<MessageBus>()
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use termtalk_proto::chat::{ChatType, MessageType};
+use termtalk_proto::direct_chats::DIRECT_CHANNEL_SEPARATOR;
+use termtalk_proto::protocol::ServerEvent;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MessageDocument {
+    pub message_id: String,
+    pub sender: String,
+    pub channel: String,
+    pub chat_type: ChatType,
+    pub msg_type: MessageType,
+    pub msg: String,
+    pub created_at: u64,
+    #[serde(default)]
+    pub participants: Vec<String>,
+}
+
+impl MessageDocument {
+    pub fn participants_for(sender: &str, channel: &str, chat_type: &ChatType) -> Vec<String> {
+        match chat_type {
+            ChatType::Direct => channel
+                .split(DIRECT_CHANNEL_SEPARATOR)
+                .map(|user| user.to_owned())
+                .collect(),
+            ChatType::Whisper => vec![sender.to_owned(), channel.to_owned()],
+            _ => vec![],
+        }
+    }
+
+    pub fn into_event(self, history: bool) -> ServerEvent {
+        ServerEvent::MessageReceived {
+            sender: self.sender,
+            channel: self.channel,
+            chat_type: self.chat_type,
+            msg_type: self.msg_type,
+            text: self.msg,
+            history,
+            queued: false,
+            message_id: Some(self.message_id),
+            created_at: Some(self.created_at),
+        }
+    }
+
+    pub fn history_events(
+        messages: Vec<MessageDocument>,
+        delivered_message_ids: &BTreeSet<String>,
+    ) -> Vec<ServerEvent> {
+        messages
+            .into_iter()
+            .filter(|message| !delivered_message_ids.contains(&message.message_id))
+            .map(|message| message.into_event(true))
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateMessageResult {
+    pub _id: String,
+    _index: String,
+    result: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_factory(message_id: &str, msg: &str) -> MessageDocument {
+        MessageDocument {
+            message_id: message_id.to_owned(),
+            sender: String::from("alice"),
+            channel: String::from("alice_bob"),
+            chat_type: ChatType::Direct,
+            msg_type: MessageType::Direct,
+            msg: msg.to_owned(),
+            created_at: 1516239022000,
+            participants: vec![String::from("alice"), String::from("bob")],
+        }
+    }
+
+    #[test]
+    fn test_history_events_skip_messages_delivered_while_offline() {
+        let delivered_message_ids = BTreeSet::from([String::from("queued")]);
+        let events = MessageDocument::history_events(
+            vec![
+                message_factory("earlier", "hi bob"),
+                message_factory("queued", "are you there?"),
+            ],
+            &delivered_message_ids,
+        );
+
+        assert_eq!(1, events.len());
+        assert!(matches!(
+            &events[0],
+            ServerEvent::MessageReceived { text, history: true, .. } if text == "hi bob"
+        ));
+    }
+}
@@ -1,4 +1,10 @@
+pub mod direct_chats;
 pub mod elastic;
-pub mod request_models;
+pub mod message_bus;
+pub mod messages;
+pub mod roles;
 pub mod rooms;
+pub mod search;
+pub mod sessions;
+pub mod tokens;
 pub mod users;
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use termtalk_proto::protocol::ClientCommand;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Moderator,
+    Admin,
+}
+
+impl Role {
+    pub fn granted_by(&self, roles: &[Role]) -> bool {
+        *self == Role::User || roles.iter().any(|role| role >= self)
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Role::User => write!(f, "user"),
+            Role::Moderator => write!(f, "moderator"),
+            Role::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+pub trait RequiredRole {
+    fn required_role(&self) -> Role;
+}
+
+impl RequiredRole for ClientCommand {
+    fn required_role(&self) -> Role {
+        match self {
+            ClientCommand::Debug | ClientCommand::DebugServer => Role::Admin,
+            _ => Role::User,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RolesForm {
+    pub roles: Vec<Role>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_are_granted_by_higher_roles() {
+        assert!(Role::User.granted_by(&[]));
+        assert!(!Role::Moderator.granted_by(&[]));
+        assert!(Role::Moderator.granted_by(&[Role::Admin]));
+        assert!(!Role::Admin.granted_by(&[Role::User, Role::Moderator]));
+
+        let roles: Vec<Role> = serde_json::from_str(r#"["moderator","admin"]"#).unwrap();
+        assert_eq!(vec![Role::Moderator, Role::Admin], roles);
+    }
+
+    #[test]
+    fn test_required_role_for_commands() {
+        assert_eq!(Role::Admin, ClientCommand::Debug.required_role());
+        assert_eq!(Role::Admin, ClientCommand::DebugServer.required_role());
+        assert_eq!(Role::User, ClientCommand::WhoAmI.required_role());
+    }
+}
@@ -1,9 +1,26 @@
 use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt};
+use termtalk_proto::protocol::ErrorCode;
+
+static MAX_ROOM_NAME_LENGTH: usize = 32;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RoomDocument {
     pub name: String,
-    pub deleted_at: String,
+    pub created_by: String,
+    pub created_at: u64,
+    pub archived_at: Option<u64>,
+    pub deleted_at: Option<u64>,
+    #[serde(default)]
+    pub moderators: Vec<String>,
+    #[serde(default)]
+    pub banned: Vec<String>,
+    #[serde(default)]
+    pub muted: Vec<String>,
+    #[serde(default)]
+    pub private: bool,
+    #[serde(default)]
+    pub members: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -12,3 +29,104 @@ pub struct CreateRoomResult {
     _index: String,
     result: String,
 }
+
+#[derive(Debug, PartialEq)]
+pub enum RoomError {
+    InvalidName(String),
+    AlreadyExists(String),
+    NotFound(String),
+    DefaultRoom,
+    Archived(String),
+    NotAllowed,
+    Banned(String),
+    NotInRoom(String),
+    InvalidTarget,
+    Private(String),
+    NotPrivate(String),
+    InvalidInvite(String),
+    Failed,
+}
+
+impl RoomError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            RoomError::InvalidName(_) | RoomError::InvalidTarget | RoomError::NotPrivate(_) => {
+                ErrorCode::BadRequest
+            }
+            RoomError::AlreadyExists(_) => ErrorCode::AlreadyExists,
+            RoomError::NotFound(_) | RoomError::NotInRoom(_) => ErrorCode::NotFound,
+            RoomError::DefaultRoom | RoomError::NotAllowed | RoomError::Private(_) => {
+                ErrorCode::Forbidden
+            }
+            RoomError::Archived(_) => ErrorCode::Archived,
+            RoomError::Banned(_) => ErrorCode::Banned,
+            RoomError::InvalidInvite(_) => ErrorCode::InvalidInvite,
+            RoomError::Failed => ErrorCode::Internal,
+        }
+    }
+}
+
+impl Error for RoomError {}
+
+impl fmt::Display for RoomError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RoomError::InvalidName(name) => write!(
+                f,
+                "'{}' is not a valid room name. Use up to {} letters, digits, '-' or '_'",
+                name, MAX_ROOM_NAME_LENGTH
+            ),
+            RoomError::AlreadyExists(name) => write!(f, "Room {} already exists", name),
+            RoomError::NotFound(name) => write!(
+                f,
+                "Room {} does not exist. Create it with /room create {}",
+                name, name
+            ),
+            RoomError::DefaultRoom => write!(f, "The default room cannot be changed"),
+            RoomError::Archived(name) => write!(f, "Room {} is already archived", name),
+            RoomError::NotAllowed => write!(f, "You do not have permission to do that"),
+            RoomError::Banned(name) => write!(f, "You are banned from room {}", name),
+            RoomError::NotInRoom(username) => write!(f, "User {} is not in this room", username),
+            RoomError::InvalidTarget => write!(f, "You cannot do that to yourself"),
+            RoomError::Private(name) => write!(
+                f,
+                "Room {} is private. Ask a member for an invite and join with /join {} (invite code)",
+                name, name
+            ),
+            RoomError::NotPrivate(name) => {
+                write!(f, "Room {} is public. Anyone can join it with /join {}", name, name)
+            }
+            RoomError::InvalidInvite(name) => {
+                write!(f, "That invite for room {} is invalid or has expired", name)
+            }
+            RoomError::Failed => write!(f, "Something went wrong"),
+        }
+    }
+}
+
+pub fn is_valid_room_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().count() <= MAX_ROOM_NAME_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_room_name() {
+        assert!(is_valid_room_name("Main"));
+        assert!(is_valid_room_name("deploys-2026_q1"));
+    }
+
+    #[test]
+    fn test_is_valid_room_name_rejects_bad_names() {
+        assert!(!is_valid_room_name(""));
+        assert!(!is_valid_room_name("has space"));
+        assert!(!is_valid_room_name("emoji🙂"));
+        assert!(!is_valid_room_name(&"a".repeat(MAX_ROOM_NAME_LENGTH + 1)));
+    }
+}
@@ -0,0 +1,157 @@
+use serde::Deserialize;
+use std::{error::Error, fmt};
+
+#[derive(Deserialize, Debug)]
+pub struct SearchParams {
+    pub q: String,
+    pub size: Option<usize>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SearchQuery {
+    pub text: String,
+    pub from: Option<String>,
+    pub channel: Option<String>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InvalidSearchQuery {
+    Empty,
+    BadFilter(String),
+    BadDate(String),
+}
+
+impl Error for InvalidSearchQuery {}
+
+impl fmt::Display for InvalidSearchQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidSearchQuery::Empty => write!(f, "Search query is empty"),
+            InvalidSearchQuery::BadFilter(filter) => {
+                write!(f, "Search filter '{}' is missing a value", filter)
+            }
+            InvalidSearchQuery::BadDate(date) => {
+                write!(f, "'{}' is not a date in the form YYYY-MM-DD", date)
+            }
+        }
+    }
+}
+
+impl SearchQuery {
+    pub fn parse(query: &str) -> Result<SearchQuery, InvalidSearchQuery> {
+        let mut search_query = SearchQuery::default();
+        let mut text_terms: Vec<&str> = vec![];
+
+        for term in query.split_whitespace() {
+            let (filter, value) = match term.split_once(':') {
+                Some((filter, value)) => (filter, value),
+                None => {
+                    text_terms.push(term);
+                    continue;
+                }
+            };
+
+            let target = match filter {
+                "from" => &mut search_query.from,
+                "in" => &mut search_query.channel,
+                "before" => &mut search_query.before,
+                "after" => &mut search_query.after,
+                _ => {
+                    text_terms.push(term);
+                    continue;
+                }
+            };
+
+            if value.is_empty() {
+                return Err(InvalidSearchQuery::BadFilter(filter.to_owned()));
+            }
+            if (filter == "before" || filter == "after") && !is_valid_date(value) {
+                return Err(InvalidSearchQuery::BadDate(value.to_owned()));
+            }
+            *target = Some(value.to_owned());
+        }
+
+        search_query.text = text_terms.join(" ");
+        if search_query == SearchQuery::default() {
+            return Err(InvalidSearchQuery::Empty);
+        }
+
+        Ok(search_query)
+    }
+}
+
+fn is_valid_date(date: &str) -> bool {
+    let parts = date.split('-').collect::<Vec<&str>>();
+    if parts.len() != 3 {
+        return false;
+    }
+
+    let expected_lengths = [4, 2, 2];
+    parts
+        .iter()
+        .zip(expected_lengths.iter())
+        .all(|(part, len)| part.len() == *len && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text_only() {
+        let search_query = SearchQuery::parse("deploy failed").unwrap();
+        assert_eq!(
+            SearchQuery {
+                text: String::from("deploy failed"),
+                ..Default::default()
+            },
+            search_query
+        );
+    }
+
+    #[test]
+    fn test_parse_filters() {
+        let search_query =
+            SearchQuery::parse("from:alice in:Main before:2026-01-01 after:2025-06-30 deploy")
+                .unwrap();
+        assert_eq!(
+            SearchQuery {
+                text: String::from("deploy"),
+                from: Some(String::from("alice")),
+                channel: Some(String::from("Main")),
+                before: Some(String::from("2026-01-01")),
+                after: Some(String::from("2025-06-30")),
+            },
+            search_query
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_filter_is_text() {
+        let search_query = SearchQuery::parse("http://localhost:8080").unwrap();
+        assert_eq!(String::from("http://localhost:8080"), search_query.text);
+    }
+
+    #[test]
+    fn test_parse_empty_query() {
+        assert_eq!(Err(InvalidSearchQuery::Empty), SearchQuery::parse("   "));
+    }
+
+    #[test]
+    fn test_parse_filter_without_value() {
+        assert_eq!(
+            Err(InvalidSearchQuery::BadFilter(String::from("from"))),
+            SearchQuery::parse("from: deploy")
+        );
+    }
+
+    #[test]
+    fn test_parse_bad_date() {
+        assert_eq!(
+            Err(InvalidSearchQuery::BadDate(String::from("yesterday"))),
+            SearchQuery::parse("before:yesterday")
+        );
+    }
+}
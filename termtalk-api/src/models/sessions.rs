@@ -0,0 +1,53 @@
+use std::{error::Error, fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SessionPolicy {
+    #[default]
+    AllowMany,
+    RejectNew,
+    KickOld,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidSessionPolicy(pub String);
+
+impl Error for InvalidSessionPolicy {}
+
+impl fmt::Display for InvalidSessionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not a session policy. Use allow_many, reject_new or kick_old",
+            self.0
+        )
+    }
+}
+
+impl FromStr for SessionPolicy {
+    type Err = InvalidSessionPolicy;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "allow_many" => Ok(SessionPolicy::AllowMany),
+            "reject_new" => Ok(SessionPolicy::RejectNew),
+            "kick_old" => Ok(SessionPolicy::KickOld),
+            _ => Err(InvalidSessionPolicy(s.to_owned())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_session_policy() {
+        assert_eq!(Ok(SessionPolicy::AllowMany), "allow_many".parse());
+        assert_eq!(Ok(SessionPolicy::RejectNew), "reject-new".parse());
+        assert_eq!(Ok(SessionPolicy::KickOld), " KICK_OLD ".parse());
+        assert_eq!(
+            Err(InvalidSessionPolicy(String::from("sometimes"))),
+            "sometimes".parse::<SessionPolicy>()
+        );
+    }
+}
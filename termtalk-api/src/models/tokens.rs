@@ -0,0 +1,218 @@
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+
+static REFRESH_TOKEN_LENGTH: usize = 48;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RefreshTokenRecord {
+    pub username: String,
+    pub jti: String,
+    #[serde(default)]
+    pub family: String,
+    pub exp: u64,
+}
+
+pub trait RefreshTokenStore {
+    fn redeem_refresh_token(&self, token: &str) -> Option<RefreshTokenRecord>;
+    fn user_refresh_tokens(&self, username: &str) -> Vec<(String, RefreshTokenRecord)>;
+    fn token_revoked(&self, jti: &str) -> bool;
+    fn family_revoked(&self, family: &str) -> bool;
+}
+
+pub fn generate_refresh_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(REFRESH_TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+pub fn redeem_for_refresh(
+    store: &impl RefreshTokenStore,
+    refresh_token: &str,
+) -> Option<RefreshTokenRecord> {
+    store
+        .redeem_refresh_token(refresh_token)
+        .filter(|record| !store.token_revoked(&record.jti) && !store.family_revoked(&record.family))
+}
+
+pub fn revoke_for_logout(
+    store: &impl RefreshTokenStore,
+    username: &str,
+    jti: &str,
+    family: &str,
+    exp: u64,
+    refresh_token: Option<&str>,
+) -> Vec<(String, String, u64)> {
+    let mut revoked = vec![(jti.to_owned(), family.to_owned(), exp)];
+    if let Some(record) = refresh_token.and_then(|token| store.redeem_refresh_token(token)) {
+        if record.username == username && record.family != family {
+            revoked.push((record.jti, record.family, record.exp));
+        }
+    }
+    for (token, record) in store.user_refresh_tokens(username) {
+        if revoked
+            .iter()
+            .any(|(_, family, _)| *family == record.family)
+        {
+            store.redeem_refresh_token(&token);
+        }
+    }
+    revoked
+}
+
+pub fn revoke_all_for_logout(store: &impl RefreshTokenStore, username: &str) -> usize {
+    let refresh_tokens = store.user_refresh_tokens(username);
+    for (token, _) in &refresh_tokens {
+        store.redeem_refresh_token(token);
+    }
+    refresh_tokens.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::{HashMap, HashSet};
+
+    #[derive(Default)]
+    struct MemoryTokenStore {
+        refresh_tokens: RefCell<HashMap<String, RefreshTokenRecord>>,
+        revoked: RefCell<HashSet<String>>,
+        revoked_families: RefCell<HashSet<String>>,
+    }
+
+    impl MemoryTokenStore {
+        fn issue(&self, token: &str, record: RefreshTokenRecord) {
+            self.refresh_tokens
+                .borrow_mut()
+                .insert(token.to_owned(), record);
+        }
+
+        fn revoke(&self, revoked: Vec<(String, String, u64)>) {
+            for (jti, family, _) in revoked {
+                self.revoked.borrow_mut().insert(jti);
+                self.revoked_families.borrow_mut().insert(family);
+            }
+        }
+    }
+
+    impl RefreshTokenStore for MemoryTokenStore {
+        fn redeem_refresh_token(&self, token: &str) -> Option<RefreshTokenRecord> {
+            self.refresh_tokens.borrow_mut().remove(token)
+        }
+
+        fn user_refresh_tokens(&self, username: &str) -> Vec<(String, RefreshTokenRecord)> {
+            self.refresh_tokens
+                .borrow()
+                .iter()
+                .filter(|(_, record)| record.username == username)
+                .map(|(token, record)| (token.clone(), record.clone()))
+                .collect()
+        }
+
+        fn token_revoked(&self, jti: &str) -> bool {
+            self.revoked.borrow().contains(jti)
+        }
+
+        fn family_revoked(&self, family: &str) -> bool {
+            self.revoked_families.borrow().contains(family)
+        }
+    }
+
+    fn record(username: &str, jti: &str, family: &str) -> RefreshTokenRecord {
+        RefreshTokenRecord {
+            username: username.to_owned(),
+            jti: jti.to_owned(),
+            family: family.to_owned(),
+            exp: 100,
+        }
+    }
+
+    #[test]
+    fn test_generate_refresh_token() {
+        let refresh_token = generate_refresh_token();
+        assert_eq!(REFRESH_TOKEN_LENGTH, refresh_token.len());
+        assert!(refresh_token.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(refresh_token, generate_refresh_token());
+    }
+
+    #[test]
+    fn test_logout_without_body_then_refresh_is_rejected() {
+        let store = MemoryTokenStore::default();
+        store.issue("token", record("mike", "jti", "laptop"));
+        store.issue("phone", record("mike", "phone_jti", "phone"));
+
+        let revoked = revoke_for_logout(&store, "mike", "jti", "laptop", 50, None);
+        assert_eq!(
+            vec![(String::from("jti"), String::from("laptop"), 50)],
+            revoked
+        );
+        store.revoke(revoked);
+
+        assert_eq!(None, redeem_for_refresh(&store, "token"));
+        assert_eq!(
+            Some(record("mike", "phone_jti", "phone")),
+            redeem_for_refresh(&store, "phone")
+        );
+    }
+
+    #[test]
+    fn test_logout_revokes_the_whole_refresh_chain() {
+        let store = MemoryTokenStore::default();
+        store.issue("rotated", record("mike", "second_jti", "laptop"));
+
+        let revoked = revoke_for_logout(&store, "mike", "first_jti", "laptop", 50, None);
+        store.revoke(revoked);
+
+        assert_eq!(None, redeem_for_refresh(&store, "rotated"));
+        store.issue("late", record("mike", "third_jti", "laptop"));
+        assert_eq!(None, redeem_for_refresh(&store, "late"));
+    }
+
+    #[test]
+    fn test_logout_with_body_revokes_the_refresh_token_family() {
+        let store = MemoryTokenStore::default();
+        store.issue("other", record("mike", "other_jti", "tablet"));
+        store.issue("stranger", record("john", "john_jti", "john"));
+
+        assert_eq!(
+            vec![
+                (String::from("jti"), String::from("laptop"), 50),
+                (String::from("other_jti"), String::from("tablet"), 100)
+            ],
+            revoke_for_logout(&store, "mike", "jti", "laptop", 50, Some("other"))
+        );
+        assert_eq!(
+            vec![(String::from("jti"), String::from("laptop"), 50)],
+            revoke_for_logout(&store, "mike", "jti", "laptop", 50, Some("stranger"))
+        );
+    }
+
+    #[test]
+    fn test_logout_everywhere_purges_every_refresh_token() {
+        let store = MemoryTokenStore::default();
+        store.issue("laptop", record("mike", "laptop_jti", "laptop"));
+        store.issue("stolen", record("mike", "stolen_jti", "stolen"));
+        store.issue("john", record("john", "john_jti", "john"));
+
+        assert_eq!(2, revoke_all_for_logout(&store, "mike"));
+        assert_eq!(None, redeem_for_refresh(&store, "laptop"));
+        assert_eq!(None, redeem_for_refresh(&store, "stolen"));
+        assert!(redeem_for_refresh(&store, "john").is_some());
+    }
+
+    #[test]
+    fn test_refresh_rejects_records_with_revoked_jti() {
+        let store = MemoryTokenStore::default();
+        store.issue("token", record("mike", "jti", "laptop"));
+        store.revoked.borrow_mut().insert(String::from("jti"));
+        assert_eq!(None, redeem_for_refresh(&store, "token"));
+
+        store.issue("token", record("mike", "fresh_jti", "laptop"));
+        assert_eq!(
+            Some(record("mike", "fresh_jti", "laptop")),
+            redeem_for_refresh(&store, "token")
+        );
+    }
+}
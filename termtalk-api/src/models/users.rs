@@ -1,11 +1,15 @@
+use crate::models::roles::Role;
 use serde::{Deserialize, Serialize};
 use std::{error::Error, fmt};
+use termtalk_proto::direct_chats::DIRECT_CHANNEL_SEPARATOR;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UserDocument {
     pub email: String,
     pub username: String,
     pub password: String,
+    #[serde(default)]
+    pub roles: Vec<Role>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -14,6 +18,11 @@ pub struct User {
     pub username: String,
     pub email: String,
     pub password: String,
+    pub roles: Vec<Role>,
+}
+
+pub fn username_reserved_chars(username: &str) -> bool {
+    username.contains(DIRECT_CHANNEL_SEPARATOR)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -32,3 +41,14 @@ impl fmt::Display for CreateUserError {
         write!(f, "Oh no, something bad went down")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_username_reserved_chars() {
+        assert!(!username_reserved_chars("alice"));
+        assert!(username_reserved_chars("bob_x"));
+    }
+}
@@ -1,16 +1,38 @@
-use crate::chat_server::chat_server::{ChatServer, ChatType};
-use crate::constants::DEFAULT_ROOM;
+use crate::chat_server::chat_server::ChatServer;
+use crate::chat_server::handlers::{
+    archive_room::ArchiveRoom, create_room::CreateRoom, create_room_invite::CreateRoomInvite,
+    delete_room::DeleteRoom, readable_rooms::ReadableRooms, rename_room::RenameRoom,
+    revoke_token::RevokeToken, revoke_user_tokens::RevokeUserTokens,
+};
+use crate::constants::{DEFAULT_ROOM, DEFAULT_SEARCH_SIZE, MAX_SEARCH_SIZE, ROOM_INVITE_TTL_SECS};
+use crate::custom_middleware::require_role::RequireRole;
 use crate::data_stores::elastic::store::ElasticStore;
-use crate::jwt::lib::{JwtToken, Payload};
+use crate::data_stores::redis::store::RedisStore;
+use crate::jwt::{
+    config::JwtConfig,
+    lib::{JwtToken, Payload},
+};
 use crate::models::elastic::DocumentMetadata;
-use crate::models::request_models::{LoginForm, RegistrationForm};
-use crate::models::users::{RegisterUserResult, UserDocument};
+use crate::models::roles::{Role, RolesForm};
+use crate::models::rooms::RoomError;
+use crate::models::search::{SearchParams, SearchQuery};
+use crate::models::tokens::{
+    generate_refresh_token, redeem_for_refresh, revoke_all_for_logout, revoke_for_logout,
+    RefreshTokenRecord,
+};
+use crate::models::users::{username_reserved_chars, RegisterUserResult, UserDocument};
 use crate::session;
-use actix::Addr;
-use actix_web::{get, post, web, web::ReqData, HttpRequest, HttpResponse, Responder};
+use actix::{Addr, MailboxError};
+use actix_web::{
+    delete, get, patch, post, put, web, web::ReqData, HttpRequest, HttpResponse, Responder,
+};
 use actix_web_actors::ws;
 use serde_json::json;
 use std::time::Instant;
+use termtalk_proto::chat::ChatType;
+use termtalk_proto::direct_chats::DIRECT_CHANNEL_SEPARATOR;
+use termtalk_proto::request_models::{LoginForm, RefreshForm, RegistrationForm, RoomForm};
+use uuid::Uuid;
 
 #[post("/register")]
 pub async fn register(
@@ -28,6 +50,11 @@ pub async fn register(
     if username_invalid || password_invalid || email_invalid {
         return HttpResponse::BadRequest().body("Bad Request");
     }
+    if username_reserved_chars(&register_form.username) {
+        return HttpResponse::BadRequest().json(json!({
+            "data": format!("Usernames may not contain '{}'", DIRECT_CHANNEL_SEPARATOR)
+        }));
+    }
 
     match elastic.users.retrieve_user(&register_form.username).await {
         Ok(val) => {
@@ -58,6 +85,8 @@ pub async fn register(
 #[post("/login")]
 pub async fn login(
     elastic: web::Data<ElasticStore>,
+    redis: web::Data<RedisStore>,
+    jwt_config: web::Data<JwtConfig>,
     login_form: web::Json<LoginForm>,
 ) -> impl Responder {
     let retrieve_user_result: DocumentMetadata<UserDocument> =
@@ -77,13 +106,129 @@ pub async fn login(
             .json(json!({"data": "Either username or password was bad"}));
     }
 
-    let jwt_token = JwtToken::from_elastic_user_document(&retrieve_user_result, None);
+    let jwt_token = JwtToken::from_elastic_user_document(&retrieve_user_result, None, &jwt_config);
+    let refresh_token = issue_refresh_token(&redis, &jwt_config, &jwt_token);
 
     HttpResponse::Ok()
         .insert_header(("Authorization", jwt_token.token))
+        .insert_header(("Refresh-Token", refresh_token))
         .json(json!(retrieve_user_result))
 }
 
+#[post("/refresh")]
+pub async fn refresh(
+    elastic: web::Data<ElasticStore>,
+    redis: web::Data<RedisStore>,
+    jwt_config: web::Data<JwtConfig>,
+    refresh_form: web::Json<RefreshForm>,
+) -> impl Responder {
+    let record = match redeem_for_refresh(redis.get_ref(), &refresh_form.refresh_token) {
+        Some(val) => val,
+        None => {
+            return HttpResponse::Unauthorized()
+                .json(json!({"data": "Refresh token is invalid or expired"}))
+        }
+    };
+    let user_document = match elastic.users.retrieve_user(&record.username).await {
+        Ok(val) => match val.hits.hits.first() {
+            Some(val) => val.clone(),
+            None => {
+                return HttpResponse::Unauthorized()
+                    .json(json!({"data": "Refresh token is invalid or expired"}))
+            }
+        },
+        Err(_) => return HttpResponse::BadRequest().json(json!({"data": "Something went wrong"})),
+    };
+    let jwt_token =
+        JwtToken::from_elastic_user_document(&user_document, Some(&record.family), &jwt_config);
+    let refresh_token = issue_refresh_token(&redis, &jwt_config, &jwt_token);
+
+    HttpResponse::Ok()
+        .insert_header(("Authorization", jwt_token.token))
+        .insert_header(("Refresh-Token", refresh_token))
+        .json(json!({"data": "Token refreshed"}))
+}
+
+#[post("/logout")]
+pub async fn logout(
+    redis: web::Data<RedisStore>,
+    jwt_config: web::Data<JwtConfig>,
+    user: Option<ReqData<Payload>>,
+    refresh_form: Option<web::Json<RefreshForm>>,
+    srv: web::Data<Addr<ChatServer>>,
+) -> impl Responder {
+    let user_payload: Payload = user.unwrap().into_inner();
+    let revoked = revoke_for_logout(
+        redis.get_ref(),
+        &user_payload.username,
+        &user_payload.jti,
+        &user_payload.fam,
+        user_payload.exp,
+        refresh_form
+            .as_ref()
+            .map(|refresh_form| refresh_form.refresh_token.as_str()),
+    );
+
+    for (jti, family, expires_at) in revoked {
+        if let Err(error) = srv
+            .send(RevokeToken {
+                username: user_payload.username.clone(),
+                jti,
+                family,
+                expires_at,
+                token_lifetime_secs: jwt_config.lifetime_secs,
+            })
+            .await
+        {
+            log::error!("Could not revoke token: {}", error);
+            return HttpResponse::InternalServerError()
+                .json(json!({"data": "Something went wrong"}));
+        }
+    }
+    HttpResponse::Ok().json(json!({"data": "Logged out"}))
+}
+
+#[post("/logout/all")]
+pub async fn logout_everywhere(
+    redis: web::Data<RedisStore>,
+    jwt_config: web::Data<JwtConfig>,
+    user: Option<ReqData<Payload>>,
+    srv: web::Data<Addr<ChatServer>>,
+) -> impl Responder {
+    let user_payload: Payload = user.unwrap().into_inner();
+    revoke_all_for_logout(redis.get_ref(), &user_payload.username);
+
+    match srv
+        .send(RevokeUserTokens {
+            username: user_payload.username.clone(),
+            token_lifetime_secs: jwt_config.lifetime_secs,
+            reason: String::from("signed out everywhere"),
+        })
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().json(json!({"data": "Logged out everywhere"})),
+        Err(error) => {
+            log::error!("Could not revoke user tokens: {}", error);
+            HttpResponse::InternalServerError().json(json!({"data": "Something went wrong"}))
+        }
+    }
+}
+
+fn issue_refresh_token(redis: &RedisStore, jwt_config: &JwtConfig, jwt_token: &JwtToken) -> String {
+    let refresh_token = generate_refresh_token();
+    redis.refresh_tokens.create_refresh_token(
+        &refresh_token,
+        &RefreshTokenRecord {
+            username: jwt_token.claims.username.clone(),
+            jti: jwt_token.claims.jti.clone(),
+            family: jwt_token.claims.fam.clone(),
+            exp: jwt_token.claims.exp,
+        },
+        jwt_config.refresh_lifetime_secs as usize,
+    );
+    refresh_token
+}
+
 #[get("/connect")]
 pub async fn connect(
     req: HttpRequest,
@@ -96,11 +241,14 @@ pub async fn connect(
     ws::start(
         session::WsChatSession {
             username: user_payload.username.clone(),
+            connection_id: Uuid::new_v4().to_string(),
             hb: Instant::now(),
             addr: srv,
             channel_name: DEFAULT_ROOM.to_owned(),
             valid_connection: false,
             chat_type: ChatType::Room,
+            family: user_payload.fam.clone(),
+            roles: user_payload.roles.clone(),
         },
         &req,
         stream,
@@ -108,6 +256,211 @@ pub async fn connect(
     .unwrap()
 }
 
+#[get("/search")]
+pub async fn search(
+    elastic: web::Data<ElasticStore>,
+    user: Option<ReqData<Payload>>,
+    params: web::Query<SearchParams>,
+    srv: web::Data<Addr<ChatServer>>,
+) -> impl Responder {
+    let user_payload: Payload = user.unwrap().into_inner();
+    let search_query = match SearchQuery::parse(&params.q) {
+        Ok(val) => val,
+        Err(error) => {
+            return HttpResponse::BadRequest().json(json!({"data": error.to_string()}));
+        }
+    };
+    let size = params
+        .size
+        .unwrap_or(DEFAULT_SEARCH_SIZE)
+        .min(MAX_SEARCH_SIZE);
+    let rooms = match srv
+        .send(ReadableRooms {
+            username: user_payload.username.clone(),
+        })
+        .await
+    {
+        Ok(val) => val,
+        Err(error) => {
+            log::error!("Failed to list readable rooms: {}", error);
+            return HttpResponse::BadRequest().json(json!({"data": "Something went wrong"}));
+        }
+    };
+
+    match elastic
+        .messages
+        .search_messages(&search_query, &user_payload.username, &rooms, size)
+        .await
+    {
+        Ok(hits) => HttpResponse::Ok().json(json!({ "data": hits })),
+        Err(error) => {
+            log::error!("Failed to search messages: {}", error);
+            HttpResponse::BadRequest().json(json!({"data": "Something went wrong"}))
+        }
+    }
+}
+
+fn room_response(result: Result<Result<(), RoomError>, MailboxError>) -> HttpResponse {
+    match result {
+        Ok(Ok(())) => HttpResponse::NoContent().finish(),
+        Ok(Err(error)) => {
+            let body = json!({"data": error.to_string()});
+            match error {
+                RoomError::NotFound(_) => HttpResponse::NotFound().json(body),
+                RoomError::AlreadyExists(_) | RoomError::Archived(_) => {
+                    HttpResponse::Conflict().json(body)
+                }
+                RoomError::Failed => HttpResponse::InternalServerError().json(body),
+                _ => HttpResponse::BadRequest().json(body),
+            }
+        }
+        Err(error) => {
+            log::error!("Failed to reach the chat server: {}", error);
+            HttpResponse::InternalServerError().json(json!({"data": "Something went wrong"}))
+        }
+    }
+}
+
+#[post("/rooms")]
+pub async fn create_room(
+    user: Option<ReqData<Payload>>,
+    room_form: web::Json<RoomForm>,
+    srv: web::Data<Addr<ChatServer>>,
+) -> impl Responder {
+    let user_payload: Payload = user.unwrap().into_inner();
+    let room_form = room_form.into_inner();
+    let result = srv
+        .send(CreateRoom {
+            username: user_payload.username,
+            room: room_form.name,
+            private: room_form.private,
+        })
+        .await;
+
+    match result {
+        Ok(Ok(())) => HttpResponse::Created().finish(),
+        _ => room_response(result),
+    }
+}
+
+#[patch("/rooms/{name}")]
+pub async fn rename_room(
+    user: Option<ReqData<Payload>>,
+    name: web::Path<String>,
+    room_form: web::Json<RoomForm>,
+    srv: web::Data<Addr<ChatServer>>,
+) -> impl Responder {
+    let user_payload: Payload = user.unwrap().into_inner();
+    room_response(
+        srv.send(RenameRoom {
+            username: user_payload.username,
+            room: name.into_inner(),
+            new_room: room_form.into_inner().name,
+        })
+        .await,
+    )
+}
+
+#[post("/rooms/{name}/invites")]
+pub async fn create_room_invite(
+    user: Option<ReqData<Payload>>,
+    name: web::Path<String>,
+    srv: web::Data<Addr<ChatServer>>,
+) -> impl Responder {
+    let user_payload: Payload = user.unwrap().into_inner();
+    let result = srv
+        .send(CreateRoomInvite {
+            username: user_payload.username,
+            room: name.into_inner(),
+        })
+        .await;
+
+    match result {
+        Ok(Ok(code)) => HttpResponse::Created().json(json!({
+            "data": { "code": code, "expires_in": ROOM_INVITE_TTL_SECS }
+        })),
+        Ok(Err(error)) => room_response(Ok(Err(error))),
+        Err(error) => room_response(Err(error)),
+    }
+}
+
+#[post("/rooms/{name}/archive")]
+pub async fn archive_room(
+    user: Option<ReqData<Payload>>,
+    name: web::Path<String>,
+    srv: web::Data<Addr<ChatServer>>,
+) -> impl Responder {
+    let user_payload: Payload = user.unwrap().into_inner();
+    room_response(
+        srv.send(ArchiveRoom {
+            username: user_payload.username,
+            room: name.into_inner(),
+        })
+        .await,
+    )
+}
+
+#[delete("/rooms/{name}")]
+pub async fn delete_room(
+    user: Option<ReqData<Payload>>,
+    name: web::Path<String>,
+    srv: web::Data<Addr<ChatServer>>,
+) -> impl Responder {
+    let user_payload: Payload = user.unwrap().into_inner();
+    room_response(
+        srv.send(DeleteRoom {
+            username: user_payload.username,
+            room: name.into_inner(),
+        })
+        .await,
+    )
+}
+
+#[put("/admin/users/{username}/roles", wrap = "RequireRole(Role::Admin)")]
+pub async fn set_user_roles(
+    elastic: web::Data<ElasticStore>,
+    jwt_config: web::Data<JwtConfig>,
+    username: web::Path<String>,
+    roles_form: web::Json<RolesForm>,
+    srv: web::Data<Addr<ChatServer>>,
+) -> impl Responder {
+    let user_document = match elastic.users.retrieve_user(&username).await {
+        Ok(val) => match val.hits.hits.first() {
+            Some(val) => val.clone(),
+            None => return HttpResponse::NotFound().json(json!({"data": "User does not exist"})),
+        },
+        Err(_) => return HttpResponse::BadRequest().json(json!({"data": "Something went wrong"})),
+    };
+    if let Err(error) = elastic
+        .users
+        .update_user_roles(&user_document._id, &roles_form.roles)
+        .await
+    {
+        log::error!("Could not update roles of {}: {}", username, error);
+        return HttpResponse::BadRequest().json(json!({"data": "Something went wrong"}));
+    }
+    if let Err(error) = srv
+        .send(RevokeUserTokens {
+            username: username.to_string(),
+            token_lifetime_secs: jwt_config.lifetime_secs,
+            reason: String::from("roles changed"),
+        })
+        .await
+    {
+        log::error!("Could not revoke tokens of {}: {}", username, error);
+        return HttpResponse::InternalServerError().json(json!({"data": "Something went wrong"}));
+    }
+
+    HttpResponse::Ok().json(json!({
+        "data": format!("Roles of {} updated. Their open sessions were closed", username)
+    }))
+}
+
+#[get("/.well-known/jwks.json")]
+pub async fn jwks(jwt_config: web::Data<JwtConfig>) -> impl Responder {
+    HttpResponse::Ok().json(jwt_config.keyring.jwks())
+}
+
 #[get("/healthcheck")]
 pub async fn healthcheck() -> impl Responder {
     HttpResponse::Ok().body("OK")
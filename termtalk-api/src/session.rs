@@ -1,17 +1,28 @@
 use std::time::{Duration, Instant};
 
-use crate::chat_server::chat_server::{ChatServer, ChatType, Message, MessageType};
+use crate::chat_server::chat_server::{ChatServer, CloseSession, Message, SwitchRoom};
 use crate::chat_server::handlers::{
-    connect::Connect, debug_server::DebugServer, disconnect::Disconnect,
-    is_user_online::IsUserOnline, join_direct::JoinDirect, join_room::JoinRoom,
+    archive_room::ArchiveRoom, connect::Connect, create_room::CreateRoom,
+    create_room_invite::CreateRoomInvite, debug_server::DebugServer, delete_room::DeleteRoom,
+    disconnect::Disconnect, fetch_history::FetchHistory, is_user_online::IsUserOnline,
+    join_direct::JoinDirect, join_room::JoinRoom, list_direct_chats::ListDirectChats,
     list_rooms::ListRooms, list_users_in_room::ListUsersInRoom, list_users_online::ListUsersOnline,
-    session_message::SessionMessage, update_session_status::UpdateSessionStatus,
+    moderate_room::ModerateRoom, refresh_presence::RefreshPresence, rename_room::RenameRoom,
+    search_messages::SearchMessages, session_message::SessionMessage, set_room_role::SetRoomRole,
+    update_session_status::UpdateSessionStatus, user_exists::UserExists,
 };
+use crate::constants::ROOM_INVITE_TTL_SECS;
+use crate::models::roles::{RequiredRole, Role};
 use actix::prelude::*;
 use actix_web::web;
 use actix_web_actors::ws;
 use actix_web_actors::ws::{CloseCode, CloseReason};
-use serde_json::json;
+use termtalk_proto::chat::{ChatType, MessageType};
+use termtalk_proto::direct_chats::DIRECT_CHANNEL_SEPARATOR;
+use termtalk_proto::protocol::{
+    ClientCommand, ClientFrame, ErrorCode, ServerEvent, ServerFrame, PROTOCOL_VERSION,
+};
+use termtalk_proto::rooms::{ModerationAction, RoomRole};
 
 const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 
@@ -20,11 +31,14 @@ const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
 #[derive(Debug)]
 pub struct WsChatSession {
     pub username: String,
+    pub connection_id: String,
     pub hb: Instant,
     pub addr: web::Data<Addr<ChatServer>>,
     pub channel_name: String,
     pub valid_connection: bool,
     pub chat_type: ChatType,
+    pub family: String,
+    pub roles: Vec<Role>,
 }
 
 impl WsChatSession {
@@ -35,6 +49,7 @@ impl WsChatSession {
 
                 act.addr.do_send(Disconnect {
                     username: act.username.clone(),
+                    connection_id: act.connection_id.clone(),
                 });
 
                 ctx.stop();
@@ -42,9 +57,716 @@ impl WsChatSession {
                 return;
             }
 
+            act.addr.do_send(RefreshPresence {
+                username: act.username.clone(),
+                connection_id: act.connection_id.clone(),
+            });
             ctx.ping(b"");
         });
     }
+
+    fn send_event(event: ServerEvent, ctx: &mut ws::WebsocketContext<Self>) {
+        ctx.text(serde_json::to_string(&ServerFrame::new(event)).unwrap());
+    }
+
+    fn send_events(events: Vec<ServerEvent>, ctx: &mut ws::WebsocketContext<Self>) {
+        for event in events {
+            WsChatSession::send_event(event, ctx);
+        }
+    }
+
+    fn send_server_notice(text: String, ctx: &mut ws::WebsocketContext<Self>) {
+        WsChatSession::send_event(ServerEvent::Notice { text }, ctx);
+    }
+
+    fn send_error(
+        id: Option<u64>,
+        code: ErrorCode,
+        message: String,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        WsChatSession::send_event(ServerEvent::Error { id, code, message }, ctx);
+    }
+
+    fn send_ack(id: Option<u64>, ctx: &mut ws::WebsocketContext<Self>) {
+        if let Some(id) = id {
+            WsChatSession::send_event(ServerEvent::Ack { id }, ctx);
+        }
+    }
+
+    fn parse_frame(text: &str) -> Result<(Option<u64>, ClientCommand), ServerEvent> {
+        if !text.starts_with('{') {
+            return ClientCommand::parse_text(text)
+                .map(|command| (None, command))
+                .map_err(|error| ServerEvent::Error {
+                    id: None,
+                    code: error.code(),
+                    message: error.to_string(),
+                });
+        }
+
+        let frame: ClientFrame =
+            serde_json::from_str(text).map_err(|error| ServerEvent::Error {
+                id: None,
+                code: ErrorCode::BadRequest,
+                message: format!("Malformed command frame: {}", error),
+            })?;
+        if frame.v != PROTOCOL_VERSION {
+            return Err(ServerEvent::Error {
+                id: frame.id,
+                code: ErrorCode::UnsupportedVersion,
+                message: format!(
+                    "Protocol version {} is not supported. This server speaks version {}",
+                    frame.v, PROTOCOL_VERSION
+                ),
+            });
+        }
+
+        Ok((frame.id, frame.command))
+    }
+
+    fn handle_command(
+        &mut self,
+        id: Option<u64>,
+        command: ClientCommand,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        let required_role = command.required_role();
+        if !required_role.granted_by(&self.roles) {
+            WsChatSession::send_error(
+                id,
+                ErrorCode::Forbidden,
+                format!("This command requires the {} role", required_role),
+                ctx,
+            );
+            return;
+        }
+
+        match command {
+            ClientCommand::SendMessage { text } => self.send_message(id, text, ctx),
+            ClientCommand::JoinRoom {
+                room,
+                invite,
+                since,
+            } => {
+                if self.chat_type == ChatType::Room && self.channel_name == room {
+                    if let Some(since) = since {
+                        self.replay_history(id, since, ctx);
+                        return;
+                    }
+                    WsChatSession::send_server_notice(
+                        format!("You are already in room {}", room),
+                        ctx,
+                    );
+                    WsChatSession::send_ack(id, ctx);
+                    return;
+                }
+                self.join_room(room, self.chat_type.clone(), invite, since, id, ctx);
+            }
+            ClientCommand::JoinDirect { username, since } => {
+                self.join_direct(id, username, since, ctx)
+            }
+            ClientCommand::Whisper { username, text } => self.whisper(id, username, text, ctx),
+            ClientCommand::ListRooms => {
+                self.addr
+                    .send(ListRooms {
+                        username: self.username.clone(),
+                    })
+                    .into_actor(self)
+                    .then(move |res, _, ctx| {
+                        match res {
+                            Ok(rooms) => {
+                                WsChatSession::send_event(ServerEvent::RoomList { rooms }, ctx);
+                                WsChatSession::send_ack(id, ctx);
+                            }
+                            Err(e) => log::debug!(
+                                "Something went wrong while listing existing rooms: {:?}",
+                                e
+                            ),
+                        }
+                        fut::ready(())
+                    })
+                    .wait(ctx);
+            }
+            ClientCommand::ListUsersOnline => {
+                self.addr
+                    .send(ListUsersOnline)
+                    .into_actor(self)
+                    .then(move |res, _, ctx| {
+                        match res {
+                            Ok(users) => {
+                                WsChatSession::send_event(
+                                    ServerEvent::UserList {
+                                        channel: None,
+                                        users,
+                                    },
+                                    ctx,
+                                );
+                                WsChatSession::send_ack(id, ctx);
+                            }
+                            Err(e) => log::debug!(
+                                "Something went wrong while fetching online users: {:?}",
+                                e
+                            ),
+                        }
+                        fut::ready(())
+                    })
+                    .wait(ctx);
+            }
+            ClientCommand::ListUsersInRoom => {
+                let room = self.channel_name.clone();
+                self.addr
+                    .send(ListUsersInRoom { room: room.clone() })
+                    .into_actor(self)
+                    .then(move |res, _, ctx| {
+                        match res {
+                            Ok(users) => {
+                                WsChatSession::send_event(
+                                    ServerEvent::UserList {
+                                        channel: Some(room),
+                                        users,
+                                    },
+                                    ctx,
+                                );
+                                WsChatSession::send_ack(id, ctx);
+                            }
+                            Err(e) => log::debug!(
+                                "Something went wrong while listing users in room {}: {:?}",
+                                &room,
+                                e
+                            ),
+                        }
+                        fut::ready(())
+                    })
+                    .wait(ctx);
+            }
+            ClientCommand::ListDirectChats => {
+                self.addr
+                    .send(ListDirectChats {
+                        username: self.username.clone(),
+                    })
+                    .into_actor(self)
+                    .then(move |res, _, ctx| {
+                        match res {
+                            Ok(Ok(direct_chats)) => {
+                                WsChatSession::send_event(
+                                    ServerEvent::DirectChatList { direct_chats },
+                                    ctx,
+                                );
+                                WsChatSession::send_ack(id, ctx);
+                            }
+                            Ok(Err(error)) => {
+                                WsChatSession::send_error(id, ErrorCode::Internal, error, ctx)
+                            }
+                            Err(e) => log::debug!(
+                                "Something went wrong while listing direct chats: {:?}",
+                                e
+                            ),
+                        }
+                        fut::ready(())
+                    })
+                    .wait(ctx);
+            }
+            ClientCommand::Search { query } => {
+                self.addr
+                    .send(SearchMessages {
+                        username: self.username.clone(),
+                        query: query.clone(),
+                    })
+                    .into_actor(self)
+                    .then(move |res, _, ctx| {
+                        match res {
+                            Ok(Ok(hits)) => {
+                                WsChatSession::send_event(
+                                    ServerEvent::SearchResults { query, hits },
+                                    ctx,
+                                );
+                                WsChatSession::send_ack(id, ctx);
+                            }
+                            Ok(Err(error)) => {
+                                WsChatSession::send_error(id, ErrorCode::BadRequest, error, ctx)
+                            }
+                            Err(e) => {
+                                log::debug!(
+                                    "Something went wrong while searching messages: {:?}",
+                                    e
+                                )
+                            }
+                        }
+                        fut::ready(())
+                    })
+                    .wait(ctx);
+            }
+            ClientCommand::WhoAmI => {
+                WsChatSession::send_event(
+                    ServerEvent::Identity {
+                        username: self.username.clone(),
+                        channel: self.channel_name.clone(),
+                        chat_type: self.chat_type.clone(),
+                    },
+                    ctx,
+                );
+                WsChatSession::send_ack(id, ctx);
+            }
+            ClientCommand::CreateRoomInvite { room } => self.invite_to_room(id, room, ctx),
+            ClientCommand::Moderate { action, username } => {
+                self.moderate(id, action, username, ctx)
+            }
+            ClientCommand::Debug => {
+                log::info!("self: {:?}", self);
+                WsChatSession::send_ack(id, ctx);
+            }
+            ClientCommand::DebugServer => {
+                self.addr.do_send(DebugServer);
+                WsChatSession::send_ack(id, ctx);
+            }
+            command => self.room_command(id, command, ctx),
+        }
+    }
+
+    fn send_message(
+        &mut self,
+        id: Option<u64>,
+        text: String,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        if text.trim().is_empty() {
+            WsChatSession::send_error(
+                id,
+                ErrorCode::BadRequest,
+                "Cannot send an empty message".to_owned(),
+                ctx,
+            );
+            return;
+        }
+        let msg_type = match self.chat_type {
+            ChatType::Room => MessageType::Room,
+            ChatType::Direct => MessageType::Direct,
+            _ => MessageType::Room,
+        };
+        self.addr.do_send(SessionMessage {
+            username: self.username.clone(),
+            msg: text,
+            channel_name: self.channel_name.clone(),
+            chat_type: self.chat_type.clone(),
+            msg_type,
+        });
+        WsChatSession::send_ack(id, ctx);
+    }
+
+    fn room_command(
+        &mut self,
+        id: Option<u64>,
+        command: ClientCommand,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        let username = self.username.clone();
+        let (request, success): (ResponseFuture<_>, String) = match command {
+            ClientCommand::CreateRoom {
+                room,
+                private: false,
+            } => (
+                Box::pin(self.addr.send(CreateRoom {
+                    username,
+                    room: room.clone(),
+                    private: false,
+                })),
+                format!("Room {} created. Join it with /join {}", room, room),
+            ),
+            ClientCommand::CreateRoom {
+                room,
+                private: true,
+            } => (
+                Box::pin(self.addr.send(CreateRoom {
+                    username,
+                    room: room.clone(),
+                    private: true,
+                })),
+                format!(
+                    "Private room {} created. Join it with /join {} and invite others with /room invite {}",
+                    room, room, room
+                ),
+            ),
+            ClientCommand::RenameRoom { room, new_room } => (
+                Box::pin(self.addr.send(RenameRoom {
+                    username,
+                    room: room.clone(),
+                    new_room: new_room.clone(),
+                })),
+                format!("Room {} renamed to {}", room, new_room),
+            ),
+            ClientCommand::ArchiveRoom { room } => (
+                Box::pin(self.addr.send(ArchiveRoom {
+                    username,
+                    room: room.clone(),
+                })),
+                format!("Room {} archived", room),
+            ),
+            ClientCommand::SetRoomRole {
+                room,
+                username: target,
+                role,
+            } => (
+                Box::pin(self.addr.send(SetRoomRole {
+                    username,
+                    room: room.clone(),
+                    target: target.clone(),
+                    role,
+                })),
+                match role {
+                    RoomRole::Member => format!("User {} is now a member of room {}", target, room),
+                    _ => format!("User {} is now a {} of room {}", target, role, room),
+                },
+            ),
+            ClientCommand::DeleteRoom { room } => (
+                Box::pin(self.addr.send(DeleteRoom {
+                    username,
+                    room: room.clone(),
+                })),
+                format!("Room {} deleted", room),
+            ),
+            command => {
+                log::debug!("{:?} is not a room command", command);
+                return;
+            }
+        };
+
+        request
+            .into_actor(self)
+            .then(move |res, _, ctx| {
+                match res {
+                    Ok(Ok(())) => {
+                        WsChatSession::send_server_notice(success, ctx);
+                        WsChatSession::send_ack(id, ctx);
+                    }
+                    Ok(Err(error)) => {
+                        WsChatSession::send_error(id, error.code(), error.to_string(), ctx)
+                    }
+                    Err(e) => log::debug!("Encountered an error while managing a room: {:?}", e),
+                }
+                fut::ready(())
+            })
+            .wait(ctx);
+    }
+
+    fn invite_to_room(
+        &mut self,
+        id: Option<u64>,
+        room: String,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        self.addr
+            .send(CreateRoomInvite {
+                username: self.username.clone(),
+                room: room.clone(),
+            })
+            .into_actor(self)
+            .then(move |res, _, ctx| {
+                match res {
+                    Ok(Ok(code)) => {
+                        WsChatSession::send_event(
+                            ServerEvent::RoomInvite {
+                                room,
+                                code,
+                                expires_in: ROOM_INVITE_TTL_SECS,
+                            },
+                            ctx,
+                        );
+                        WsChatSession::send_ack(id, ctx);
+                    }
+                    Ok(Err(error)) => {
+                        WsChatSession::send_error(id, error.code(), error.to_string(), ctx)
+                    }
+                    Err(e) => log::debug!("Encountered an error while creating an invite: {:?}", e),
+                }
+                fut::ready(())
+            })
+            .wait(ctx);
+    }
+
+    fn resolve_recipient<F>(
+        &mut self,
+        id: Option<u64>,
+        recipient: String,
+        ctx: &mut ws::WebsocketContext<Self>,
+        on_resolved: F,
+    ) where
+        F: FnOnce(&mut Self, bool, &mut ws::WebsocketContext<Self>) + 'static,
+    {
+        self.addr
+            .send(IsUserOnline {
+                username: recipient.clone(),
+            })
+            .into_actor(self)
+            .then(move |res, act, ctx| {
+                match res {
+                    Ok(true) => on_resolved(act, true, ctx),
+                    Ok(false) => {
+                        act.addr
+                            .send(UserExists {
+                                username: recipient.clone(),
+                            })
+                            .into_actor(act)
+                            .then(move |res, act, ctx| {
+                                match res {
+                                    Ok(true) => on_resolved(act, false, ctx),
+                                    Ok(false) => WsChatSession::send_error(
+                                        id,
+                                        ErrorCode::UserNotFound,
+                                        format!("User {} does not exist. Try again", &recipient),
+                                        ctx,
+                                    ),
+                                    Err(e) => log::debug!(
+                                        "Encountered an error while trying to determine if user {} exists. Error: {:?}",
+                                        &recipient,
+                                        e
+                                    ),
+                                }
+                                fut::ready(())
+                            })
+                            .wait(ctx);
+                    }
+                    Err(e) => log::debug!(
+                        "Encountered an error while trying to determine if user {} is online. Error: {:?}",
+                        &recipient,
+                        e
+                    ),
+                }
+                fut::ready(())
+            })
+            .wait(ctx);
+    }
+
+    fn whisper(
+        &mut self,
+        id: Option<u64>,
+        recipient: String,
+        text: String,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        if recipient == self.username {
+            WsChatSession::send_error(
+                id,
+                ErrorCode::BadRequest,
+                "You cannot whisper to yourself".to_owned(),
+                ctx,
+            );
+            return;
+        }
+
+        self.resolve_recipient(id, recipient.clone(), ctx, move |act, _, ctx| {
+            act.addr.do_send(SessionMessage {
+                username: act.username.clone(),
+                msg: text,
+                channel_name: recipient,
+                chat_type: ChatType::Whisper,
+                msg_type: MessageType::Whisper,
+            });
+            WsChatSession::send_ack(id, ctx);
+        });
+    }
+
+    fn join_direct(
+        &mut self,
+        id: Option<u64>,
+        recipient: String,
+        since: Option<u64>,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        if recipient == self.username {
+            WsChatSession::send_error(
+                id,
+                ErrorCode::BadRequest,
+                "You cannot direct chat yourself".to_owned(),
+                ctx,
+            );
+            return;
+        }
+
+        self.resolve_recipient(id, recipient.clone(), ctx, move |act, user_online, ctx| {
+            let mut user_vec = vec![act.username.clone(), recipient.clone()];
+            user_vec.sort();
+
+            let channel_name = user_vec.join(DIRECT_CHANNEL_SEPARATOR);
+            if channel_name == act.channel_name {
+                if let Some(since) = since {
+                    act.replay_history(id, since, ctx);
+                    return;
+                }
+                WsChatSession::send_server_notice(
+                    format!("You are already direct messaging user {}", recipient),
+                    ctx,
+                );
+                WsChatSession::send_ack(id, ctx);
+                return;
+            }
+
+            act.addr
+                .send(JoinDirect {
+                    recipient: recipient.to_owned(),
+                    sender: act.username.clone(),
+                    connection_id: act.connection_id.clone(),
+                    channel_name: channel_name.clone(),
+                    previous_channel_name: act.channel_name.clone(),
+                    previous_chat_type: act.chat_type.clone(),
+                    since,
+                })
+                .into_actor(act)
+                .then(move |res, act, ctx| {
+                    if let Ok(scrollback) = res {
+                        WsChatSession::send_event(
+                            ServerEvent::ChannelJoined {
+                                channel: act.channel_name.clone(),
+                                chat_type: ChatType::Direct,
+                            },
+                            ctx,
+                        );
+                        WsChatSession::send_events(scrollback, ctx);
+                    }
+                    if !user_online {
+                        WsChatSession::send_server_notice(
+                            format!(
+                                "User {} is offline. Your messages will be delivered when they next connect",
+                                &recipient
+                            ),
+                            ctx,
+                        );
+                    }
+                    WsChatSession::send_ack(id, ctx);
+                    fut::ready(())
+                })
+                .wait(ctx);
+            act.addr.do_send(UpdateSessionStatus {
+                username: act.username.clone(),
+                connection_id: act.connection_id.clone(),
+                channel_name: channel_name.clone(),
+                chat_type: ChatType::Direct,
+            });
+
+            act.channel_name = channel_name;
+            act.chat_type = ChatType::Direct;
+        });
+    }
+
+    fn moderate(
+        &mut self,
+        id: Option<u64>,
+        action: ModerationAction,
+        target: String,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        if self.chat_type != ChatType::Room {
+            WsChatSession::send_error(
+                id,
+                ErrorCode::BadRequest,
+                "Moderation commands can only be used inside a room".to_owned(),
+                ctx,
+            );
+            return;
+        }
+
+        self.addr
+            .send(ModerateRoom {
+                username: self.username.clone(),
+                room: self.channel_name.clone(),
+                target,
+                action,
+                roles: self.roles.clone(),
+            })
+            .into_actor(self)
+            .then(move |res, _, ctx| {
+                match res {
+                    Ok(Ok(())) => WsChatSession::send_ack(id, ctx),
+                    Ok(Err(error)) => {
+                        WsChatSession::send_error(id, error.code(), error.to_string(), ctx)
+                    }
+                    Err(e) => log::debug!("Encountered an error while moderating a room: {:?}", e),
+                }
+                fut::ready(())
+            })
+            .wait(ctx);
+    }
+
+    fn replay_history(
+        &mut self,
+        id: Option<u64>,
+        since: u64,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        self.addr
+            .send(FetchHistory {
+                channel_name: self.channel_name.clone(),
+                chat_type: self.chat_type.clone(),
+                since,
+            })
+            .into_actor(self)
+            .then(move |res, act, ctx| {
+                WsChatSession::send_event(
+                    ServerEvent::ChannelJoined {
+                        channel: act.channel_name.clone(),
+                        chat_type: act.chat_type.clone(),
+                    },
+                    ctx,
+                );
+                match res {
+                    Ok(history) => WsChatSession::send_events(history, ctx),
+                    Err(e) => log::debug!("Encountered an error while replaying history: {:?}", e),
+                }
+                WsChatSession::send_ack(id, ctx);
+                fut::ready(())
+            })
+            .wait(ctx);
+    }
+
+    fn join_room(
+        &mut self,
+        channel_name: String,
+        previous_chat_type: ChatType,
+        invite: Option<String>,
+        since: Option<u64>,
+        id: Option<u64>,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        self.addr
+            .send(JoinRoom {
+                username: self.username.clone(),
+                connection_id: self.connection_id.clone(),
+                channel_name: channel_name.clone(),
+                chat_type: ChatType::Room,
+                previous_channel_name: self.channel_name.clone(),
+                previous_chat_type,
+                invite,
+                since,
+            })
+            .into_actor(self)
+            .then(move |res, act, ctx| {
+                match res {
+                    Ok(Ok(scrollback)) => {
+                        act.channel_name = channel_name.clone();
+                        act.chat_type = ChatType::Room;
+                        WsChatSession::send_event(
+                            ServerEvent::ChannelJoined {
+                                channel: channel_name,
+                                chat_type: ChatType::Room,
+                            },
+                            ctx,
+                        );
+                        WsChatSession::send_events(scrollback, ctx);
+                        WsChatSession::send_ack(id, ctx);
+                    }
+                    Ok(Err(error)) => {
+                        WsChatSession::send_error(id, error.code(), error.to_string(), ctx)
+                    }
+                    Err(e) => log::debug!(
+                        "Encountered an error while trying to join room {}. Error: {:?}",
+                        &channel_name,
+                        e
+                    ),
+                }
+                fut::ready(())
+            })
+            .wait(ctx);
+    }
 }
 
 impl Actor for WsChatSession {
@@ -55,9 +777,13 @@ impl Actor for WsChatSession {
         self.addr
             .send(Connect {
                 username: self.username.clone(),
+                connection_id: self.connection_id.clone(),
                 channel_name: self.channel_name.clone(),
                 chat_type: self.chat_type.clone(),
-                addr: addr.recipient(),
+                addr: addr.clone().recipient(),
+                switch_room_addr: addr.clone().recipient(),
+                close_addr: addr.recipient(),
+                family: self.family.clone(),
             })
             .into_actor(self)
             .then(|res, act, ctx| {
@@ -65,7 +791,7 @@ impl Actor for WsChatSession {
                     Ok(res) => {
                         if res == false {
                             let close_reason = CloseReason {
-                                code: CloseCode::Invalid,
+                                code: CloseCode::Policy,
                                 description: Some(
                                     "user is already connected from another client".to_string(),
                                 ),
@@ -85,19 +811,21 @@ impl Actor for WsChatSession {
             })
             .wait(ctx);
 
-        self.addr.do_send(JoinRoom {
-            username: self.username.clone(),
-            channel_name: self.channel_name.clone(),
-            chat_type: ChatType::Room,
-            previous_channel_name: self.channel_name.clone(),
-            previous_chat_type: ChatType::NoPreviousChatType,
-        });
+        self.join_room(
+            self.channel_name.clone(),
+            ChatType::NoPreviousChatType,
+            None,
+            None,
+            None,
+            ctx,
+        );
     }
 
     fn stopping(&mut self, _: &mut Self::Context) -> Running {
         if self.valid_connection {
             self.addr.do_send(Disconnect {
                 username: self.username.clone(),
+                connection_id: self.connection_id.clone(),
             });
         }
         Running::Stop
@@ -108,7 +836,39 @@ impl Handler<Message> for WsChatSession {
     type Result = ();
 
     fn handle(&mut self, msg: Message, ctx: &mut Self::Context) {
-        ctx.text(serde_json::to_string(&msg).unwrap());
+        WsChatSession::send_event(msg.0, ctx);
+    }
+}
+
+impl Handler<SwitchRoom> for WsChatSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: SwitchRoom, ctx: &mut Self::Context) {
+        if msg.rejoin {
+            self.join_room(msg.room, self.chat_type.clone(), None, None, None, ctx);
+        } else {
+            self.channel_name = msg.room;
+            self.chat_type = ChatType::Room;
+            WsChatSession::send_event(
+                ServerEvent::ChannelJoined {
+                    channel: self.channel_name.clone(),
+                    chat_type: ChatType::Room,
+                },
+                ctx,
+            );
+        }
+    }
+}
+
+impl Handler<CloseSession> for WsChatSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: CloseSession, ctx: &mut Self::Context) {
+        ctx.close(Some(CloseReason {
+            code: CloseCode::Policy,
+            description: Some(msg.reason),
+        }));
+        ctx.stop();
     }
 }
 
@@ -130,274 +890,10 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WsChatSession {
             ws::Message::Pong(_) => {
                 self.hb = Instant::now();
             }
-            ws::Message::Text(text) => {
-                let m = text.trim();
-                if m.starts_with('/') {
-                    let v: Vec<&str> = m.splitn(3, ' ').collect();
-                    match v[0] {
-                        "/debug" => log::info!("self: {:?}", self),
-                        "/debug_server" => {
-                            self.addr.do_send(DebugServer);
-                        },
-                        "/whoami" => {
-                            let whereami = match &self.chat_type {
-                                ChatType::Direct => {
-                                    let users_iterator = self.channel_name.split("_");
-                                    let mut direct_user = String::from("");
-                                    for user in users_iterator {
-                                        if user != self.username {
-                                            direct_user = user.to_owned();
-                                            break;
-                                        }
-                                    };
-                                    format!("directly messaging {}", direct_user)
-                                },
-                                ChatType::Room => {
-                                    format!("in room {}", self.channel_name)
-                                },
-                                _ => String::from(""),
-                            };
-                            let msg_struct = Message {text: format!("You are {} and you are currently {}", self.username, whereami), color: "green".to_string()};
-                            let msg = serde_json::to_string(&msg_struct).unwrap();
-                            ctx.text(msg);
-                        }
-                        "/o" | "/online" => {
-
-                            self.addr
-                                .send(ListUsersOnline)
-                                .into_actor(self)
-                                .then(|res, _, ctx| {
-                                    match res {
-                                        Ok(connected_users) => {
-
-                                            ctx.text(json!({"text": "Users currently online", "color": "green"}).to_string());
-                                            for user in connected_users {
-                                                let msg_struct = Message {text: user.to_owned(), color: "green".to_string()};
-                                                let msg = serde_json::to_string(&msg_struct).unwrap();
-                                                ctx.text(msg);
-                                            }
-                                        },
-                                        _ => log::debug!("Something went wrong while fetching online users"),
-                                    }
-                                    fut::ready(())
-                                })
-                                .wait(ctx)
-                        }
-                        "/j" | "/join" => {
-                            if v.len() == 2 {
-                                let channel_name = v[1].to_owned();
-                                if self.chat_type == ChatType::Room && self.channel_name == channel_name {
-                                    let msg = Message{
-                                        text: format!("You are already in room {}", channel_name),
-                                        color: "green".to_owned(),
-                                    };
-                                    ctx.text(serde_json::to_string(&msg).unwrap());
-                                    return
-                                }
-                                self.addr.do_send(JoinRoom {
-                                    username: self.username.clone(),
-                                    channel_name: channel_name.clone(),
-                                    chat_type: ChatType::Room,
-                                    previous_channel_name: self.channel_name.clone(),
-                                    previous_chat_type: self.chat_type.clone(),
-                                });
-                                self.addr
-                                    .do_send(UpdateSessionStatus{
-                                        username: self.username.clone(),
-                                        channel_name: channel_name.clone(),
-                                        chat_type: ChatType::Room,
-                                    });
-                                self.channel_name =  channel_name.clone();
-                                self.chat_type = ChatType::Room;
-
-                            } else {
-                                ctx.text("!!! room name is required");
-                            }
-                        }
-                        "/r" | "/rooms" => {
-                            self.addr
-                                .send(ListRooms)
-                                .into_actor(self)
-                                .then(|res, _act, ctx| {
-                                    match res {
-                                        Ok(list_rooms) => {
-                                            ctx.text(json!({"text": "List of Existing Rooms", "color": "green"}).to_string());
-                                            for room in list_rooms {
-                                                let msg_struct = Message {text: room.to_owned(), color: "green".to_string()};
-                                                let msg = serde_json::to_string(&msg_struct).unwrap();
-                                                ctx.text(msg);
-                                            }
-                                        },
-                                        _ => log::debug!("Something went wrong while list of existing rooms"),
-                                    }
-                                    fut::ready(())
-                                })
-                                .wait(ctx);
-                        }
-                        "/h" | "/here" => {
-                            self.addr
-                            .send(ListUsersInRoom {
-                                room: self.channel_name.clone(),
-                            })
-                            .into_actor(self)
-                            .then(|res, _act, ctx| {
-                                match res {
-                                    Ok(rooms_users) => {
-                                        ctx.text(json!({"text": "List of who is here", "color": "green"}).to_string());
-                                        for user in rooms_users {
-                                            let msg_struct = Message {text: user.to_owned(), color: "green".to_string()};
-                                            let msg = serde_json::to_string(&msg_struct).unwrap();
-                                            ctx.text(msg);
-                                        }
-                                    },
-                                    _ => log::debug!("Something went wrong while list of existing rooms"),
-                                }
-                                fut::ready(())
-                            })
-                            .wait(ctx);
-
-                        }
-                        "/w" | "/whisper" => {
-                            if v.len() == 3 {
-
-                                let recipient = v[1].to_owned();
-                                if recipient == self.username {
-                                    let msg = Message{
-                                        text: "You cannot whisper to yourself".to_owned(),
-                                        color: "green".to_owned(),
-                                    };
-                                    ctx.text(serde_json::to_string(&msg).unwrap());
-                                    return
-                                };
-                                let text = v[2].to_owned();
-
-                                self.addr
-                                    .send(IsUserOnline{username: recipient.to_owned()})
-                                    .into_actor(self)
-                                    .then(move |res, act, ctx| {
-                                        match res {
-                                            Ok(user_exists) => {
-                                                if user_exists {
-                                                    act.addr.do_send(SessionMessage {
-                                                        username: act.username.clone(),
-                                                        msg: text,
-                                                        channel_name: recipient.clone(),
-                                                        chat_type: ChatType::Whisper,
-                                                        msg_type: MessageType::Whisper,
-                                                    })
-
-                                                } else {
-                                                    let mut msg = Message{
-                                                        text: "".to_owned(),
-                                                        color: "green".to_owned(),
-                                                    };
-                                                    msg.text = format!("User {} is not currently online. Try again", &recipient).to_owned();
-                                                    ctx.text(serde_json::to_string(&msg).unwrap());
-                                                }
-                                            },
-                                            e => log::debug!("Encountered an error while trying to determine if user {} exists. Error: {:?}", &recipient, e),
-                                        }
-
-                                        fut::ready(())
-                                    })
-                                    .wait(ctx);
-                            } else {
-                                let mut msg = Message{
-                                    text: "".to_owned(),
-                                    color: "green".to_owned(),
-                                };
-                                msg.text = "/w | /whisper command requires that you pass in a username as an argument followed by a message. Try again".to_owned();
-                                ctx.text(serde_json::to_string(&msg).unwrap());
-                            }
-
-                        }
-                        "/d" | "/direct" => {
-                            let mut msg = Message{
-                                text: "".to_owned(),
-                                color: "green".to_owned(),
-                            };
-                            if v.len() == 2 {
-
-                                let recipient = v[1].to_owned();
-                                if recipient == self.username {
-                                    let msg = Message{
-                                        text: "You cannot direct chat yourself".to_owned(),
-                                        color: "green".to_owned(),
-                                    };
-                                    ctx.text(serde_json::to_string(&msg).unwrap());
-                                    return
-                                }
-                                self.addr
-                                    .send(IsUserOnline{username: recipient.to_owned()})
-                                    .into_actor(self)
-                                    .then(move |res, act, ctx| {
-
-                                        match res {
-                                            Ok(user_online) => {
-                                                if user_online {
-                                                    let mut user_vec = vec![act.username.clone(), recipient.clone()];
-                                                    user_vec.sort();
-
-                                                    let channel_name = user_vec.join("_");
-                                                    if channel_name == act.channel_name {
-                                                        let msg = Message{
-                                                            text: format!("You are already direct messaging user {}", recipient),
-                                                            color: "green".to_owned(),
-                                                        };
-                                                        ctx.text(serde_json::to_string(&msg).unwrap());
-                                                        return fut::ready(());
-                                                    }
-
-                                                    act.addr
-                                                    .do_send(JoinDirect{
-                                                        recipient: recipient.to_owned(),
-                                                        sender: act.username.clone(),
-                                                        channel_name: channel_name.clone(),
-                                                        previous_channel_name: act.channel_name.clone(),
-                                                        previous_chat_type: act.chat_type.clone(),
-                                                    });
-                                                    act.addr
-                                                        .do_send(UpdateSessionStatus{
-                                                            username: act.username.clone(),
-                                                            channel_name: channel_name.clone(),
-                                                            chat_type: ChatType::Direct,
-                                                        });
-
-                                                    act.channel_name = channel_name.clone();
-                                                    act.chat_type = ChatType::Direct;
-                                                } else {
-                                                    msg.text = format!("User {} is not currently online. Try again", &recipient).to_owned();
-                                                    ctx.text(serde_json::to_string(&msg).unwrap());
-                                                }
-
-                                            },
-                                            e => log::debug!("Encountered an error while trying to determine if user {} exists. Error: {:?}", &recipient, e),
-                                        }
-                                        fut::ready(())
-                                    })
-                                    .wait(ctx);
-                            } else {
-                                msg.text = "/d | /direct command requires that you pass in a username as an argument. Try again".to_owned();
-                                ctx.text(serde_json::to_string(&msg).unwrap());
-                            }
-                        }
-                        _ => ctx.text(format!("!!! unknown command: {:?}", m)),
-                    }
-                } else {
-                    let msg_type = match self.chat_type {
-                        ChatType::Room => MessageType::Room,
-                        ChatType::Direct => MessageType::Direct,
-                        _ => MessageType::Room,
-                    };
-                    self.addr.do_send(SessionMessage {
-                        username: self.username.clone(),
-                        msg: m.to_owned(),
-                        channel_name: self.channel_name.clone(),
-                        chat_type: self.chat_type.clone(),
-                        msg_type: msg_type,
-                    })
-                }
-            }
+            ws::Message::Text(text) => match WsChatSession::parse_frame(text.trim()) {
+                Ok((id, command)) => self.handle_command(id, command, ctx),
+                Err(error) => WsChatSession::send_event(error, ctx),
+            },
             ws::Message::Binary(_) => println!("Unexpected binary"),
             ws::Message::Close(reason) => {
                 ctx.close(reason);
@@ -0,0 +1,92 @@
+use crate::chat_server::{chat_server::ChatServer, handlers::instance_message::InstanceMessage};
+use crate::data_stores::redis::{publish_chat_messages::PubSubChatMessages, store::RedisStore};
+use crate::models::message_bus::MessageBus;
+use actix::Addr;
+use std::thread;
+use std::time::Duration;
+
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+pub fn subscribe(chat_server: Addr<ChatServer>, redis_store: RedisStore, instance_id: String) {
+    loop {
+        let result = match redis_store.publish_chat_messages.bus() {
+            MessageBus::PubSub => subscribe_to_channel(&chat_server, &redis_store, &instance_id),
+            MessageBus::Streams => consume_stream(&chat_server, &redis_store, &instance_id),
+        };
+        if let Err(error) = result {
+            log::error!(
+                "Lost the chat message subscription for instance {}: {}",
+                &instance_id,
+                error
+            );
+        }
+        thread::sleep(RESUBSCRIBE_DELAY);
+    }
+}
+
+fn parse(payload: &str) -> Option<InstanceMessage> {
+    match serde_json::from_str(payload) {
+        Ok(val) => Some(val),
+        Err(error) => {
+            log::error!("Dropping malformed chat message {}: {}", payload, error);
+            None
+        }
+    }
+}
+
+fn subscribe_to_channel(
+    chat_server: &Addr<ChatServer>,
+    redis_store: &RedisStore,
+    instance_id: &str,
+) -> redis::RedisResult<()> {
+    let mut redis_conn = redis_store.redis.get_connection()?;
+    let mut pubsub = redis_conn.as_pubsub();
+    pubsub.subscribe(PubSubChatMessages::instance_channel(instance_id))?;
+
+    loop {
+        let msg: redis::Msg = pubsub.get_message()?;
+        let msg_payload: String = msg.get_payload()?;
+        if let Some(instance_message) = parse(&msg_payload) {
+            chat_server.do_send(instance_message);
+        }
+    }
+}
+
+fn consume_stream(
+    chat_server: &Addr<ChatServer>,
+    redis_store: &RedisStore,
+    instance_id: &str,
+) -> redis::RedisResult<()> {
+    let chat_messages = &redis_store.publish_chat_messages;
+    let mut redis_conn = redis_store.redis.get_connection()?;
+    let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+    chat_messages.create_consumer_group(&mut redis_conn, instance_id)?;
+
+    let mut start_id = "0";
+    loop {
+        let entries = chat_messages.read_stream(&mut redis_conn, instance_id, start_id)?;
+        if entries.is_empty() && start_id == "0" {
+            start_id = ">";
+            continue;
+        }
+        for (stream_id, payload) in entries {
+            let instance_message = match payload {
+                Some(payload) => parse(&payload),
+                None => {
+                    log::error!("Dropping chat message {} without a payload", &stream_id);
+                    None
+                }
+            };
+            if let Some(instance_message) = instance_message {
+                if let Err(error) = runtime.block_on(chat_server.send(instance_message)) {
+                    return Err(redis::RedisError::from((
+                        redis::ErrorKind::ClientError,
+                        "Chat server did not handle the chat message",
+                        format!("{}: {}", &stream_id, error),
+                    )));
+                }
+            }
+            chat_messages.ack_stream(&mut redis_conn, instance_id, &stream_id)?;
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn time_as_millis_since_epoch() -> u64 {
+    let start = SystemTime::now();
+    let since_the_epoch = start
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards!");
+
+    since_the_epoch.as_millis() as u64
+}
+
+pub fn time_as_secs_since_epoch() -> u64 {
+    let start = SystemTime::now();
+    let since_the_epoch = start
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards!");
+
+    since_the_epoch.as_secs()
+}
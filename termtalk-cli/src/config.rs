@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+static CONFIG_DIR_ENV: &str = "TERMTALK_CONFIG_DIR";
+
+static CONFIG_FILE: &str = "profiles.toml";
+
+static TOKENS_DIR: &str = "tokens";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub protocol: String,
+    pub host: String,
+    pub username: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct Config {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[derive(Clone)]
+pub struct ConfigDir {
+    path: PathBuf,
+}
+
+impl ConfigDir {
+    pub fn new(path: PathBuf) -> Self {
+        ConfigDir { path }
+    }
+
+    pub fn locate() -> Option<Self> {
+        match env::var(CONFIG_DIR_ENV) {
+            Ok(path) => Some(ConfigDir::new(PathBuf::from(path))),
+            Err(_) => dirs::config_dir().map(|path| ConfigDir::new(path.join("termtalk"))),
+        }
+    }
+
+    fn token_path(&self, profile: &str) -> PathBuf {
+        self.path
+            .join(TOKENS_DIR)
+            .join(format!("{}.token", profile))
+    }
+
+    pub fn load(&self) -> io::Result<Config> {
+        match fs::read_to_string(self.path.join(CONFIG_FILE)) {
+            Ok(contents) => {
+                toml::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, config: &Config) -> io::Result<()> {
+        fs::create_dir_all(&self.path)?;
+        let contents =
+            toml::to_string(config).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(self.path.join(CONFIG_FILE), contents)
+    }
+
+    fn load_token_line(&self, profile: &str, line: usize) -> Option<String> {
+        let tokens = fs::read_to_string(self.token_path(profile)).ok()?;
+        let token = tokens.lines().nth(line)?.trim();
+        match token.is_empty() {
+            true => None,
+            false => Some(token.to_owned()),
+        }
+    }
+
+    pub fn load_token(&self, profile: &str) -> Option<String> {
+        self.load_token_line(profile, 0)
+    }
+
+    pub fn load_refresh_token(&self, profile: &str) -> Option<String> {
+        self.load_token_line(profile, 1)
+    }
+
+    pub fn save_token(
+        &self,
+        profile: &str,
+        token: &str,
+        refresh_token: Option<&str>,
+    ) -> io::Result<()> {
+        let tokens_dir = self.path.join(TOKENS_DIR);
+        fs::create_dir_all(&tokens_dir)?;
+        #[cfg(unix)]
+        fs::set_permissions(&tokens_dir, fs::Permissions::from_mode(0o700))?;
+
+        let token_path = self.token_path(profile);
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(&token_path)?;
+        #[cfg(unix)]
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(format!("{}\n{}\n", token, refresh_token.unwrap_or_default()).as_bytes())
+    }
+
+    pub fn remove_token(&self, profile: &str) -> io::Result<()> {
+        match fs::remove_file(self.token_path(profile)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ActiveProfile {
+    pub name: String,
+    pub profile: Profile,
+    config_dir: Option<ConfigDir>,
+}
+
+impl ActiveProfile {
+    pub fn resolve(name: &str, protocol: &str, host: &str) -> Self {
+        let config_dir = ConfigDir::locate();
+        let saved = config_dir
+            .as_ref()
+            .and_then(|config_dir| match config_dir.load() {
+                Ok(config) => config.profiles.get(name).cloned(),
+                Err(e) => {
+                    log::warn!("Could not read the termtalk-cli profiles: {}", e);
+                    None
+                }
+            });
+
+        ActiveProfile {
+            name: name.to_owned(),
+            profile: saved.unwrap_or_else(|| Profile {
+                protocol: protocol.to_owned(),
+                host: host.to_owned(),
+                username: None,
+            }),
+            config_dir,
+        }
+    }
+
+    pub fn saved_token(&self) -> Option<String> {
+        self.config_dir.as_ref()?.load_token(&self.name)
+    }
+
+    pub fn saved_refresh_token(&self) -> Option<String> {
+        self.config_dir.as_ref()?.load_refresh_token(&self.name)
+    }
+
+    pub fn save_tokens(&self, token: &str, refresh_token: Option<&str>) -> io::Result<()> {
+        match &self.config_dir {
+            Some(config_dir) => config_dir.save_token(&self.name, token, refresh_token),
+            None => Ok(()),
+        }
+    }
+
+    pub fn remember(
+        &mut self,
+        username: &str,
+        token: &str,
+        refresh_token: Option<&str>,
+    ) -> io::Result<()> {
+        self.profile.username = Some(username.to_owned());
+        let config_dir = match &self.config_dir {
+            Some(config_dir) => config_dir,
+            None => return Ok(()),
+        };
+        let mut config = config_dir.load()?;
+        config
+            .profiles
+            .insert(self.name.clone(), self.profile.clone());
+        config_dir.save(&config)?;
+        config_dir.save_token(&self.name, token, refresh_token)
+    }
+
+    pub fn forget_token(&self) -> io::Result<()> {
+        match &self.config_dir {
+            Some(config_dir) => config_dir.remove_token(&self.name),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_dir_factory(name: &str) -> ConfigDir {
+        let path = env::temp_dir().join(format!("termtalk-cli-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        ConfigDir::new(path)
+    }
+
+    #[test]
+    fn test_profiles_round_trip() {
+        let config_dir = config_dir_factory("profiles");
+        assert_eq!(Config::default(), config_dir.load().unwrap());
+
+        let mut config = Config::default();
+        config.profiles.insert(
+            "work".to_owned(),
+            Profile {
+                protocol: "https".to_owned(),
+                host: "chat.example.com".to_owned(),
+                username: Some("alice".to_owned()),
+            },
+        );
+        config_dir.save(&config).unwrap();
+        assert_eq!(config, config_dir.load().unwrap());
+
+        let _ = fs::remove_dir_all(&config_dir.path);
+    }
+
+    #[test]
+    fn test_tokens_are_private() {
+        let config_dir = config_dir_factory("tokens");
+        assert_eq!(None, config_dir.load_token("work"));
+
+        config_dir
+            .save_token("work", "old", Some("old_refresh"))
+            .unwrap();
+        config_dir
+            .save_token("work", "new", Some("refresh"))
+            .unwrap();
+        assert_eq!(Some("new".to_owned()), config_dir.load_token("work"));
+        assert_eq!(
+            Some("refresh".to_owned()),
+            config_dir.load_refresh_token("work")
+        );
+        config_dir.save_token("work", "new", None).unwrap();
+        assert_eq!(Some("new".to_owned()), config_dir.load_token("work"));
+        assert_eq!(None, config_dir.load_refresh_token("work"));
+
+        #[cfg(unix)]
+        {
+            let mode = fs::metadata(config_dir.token_path("work"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(0o600, mode & 0o777);
+        }
+
+        config_dir.remove_token("work").unwrap();
+        assert_eq!(None, config_dir.load_token("work"));
+        config_dir.remove_token("work").unwrap();
+
+        let _ = fs::remove_dir_all(&config_dir.path);
+    }
+
+    #[test]
+    fn test_profile_names() {
+        assert!(is_valid_profile_name("work-2"));
+        assert!(!is_valid_profile_name(""));
+        assert!(!is_valid_profile_name("../work"));
+    }
+}
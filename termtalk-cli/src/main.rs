@@ -1,71 +1,27 @@
-use actix_web::web::Bytes;
-use awc::ws;
+mod config;
+mod render;
+mod script;
+mod tui;
+
+use clap::{Parser, Subcommand};
 use colored::*;
-use futures_util::{SinkExt as _, StreamExt as _};
-use serde_json::json;
+use config::ActiveProfile;
+use futures_util::StreamExt as _;
+use render::{render_event, RenderedLine, HELP};
 use std::io::Write;
-use std::{env, io, process, str, thread};
+use std::path::PathBuf;
+use std::{env, io, process, thread};
+use termtalk_client::client::{Client, TokenUpdate};
+use termtalk_client::connection::{Connection, Reconnect};
+use termtalk_client::error::ClientError;
 use tokio::{select, sync::mpsc};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 extern crate colored;
-use serde::Deserialize;
+use termtalk_proto::protocol::ClientCommand;
 
 extern crate redis;
 
-#[derive(Deserialize)]
-struct Message {
-    text: String,
-    color: String,
-}
-
-async fn login_request(
-    username: &str,
-    password: &str,
-) -> Result<reqwest::Response, reqwest::Error> {
-    let client = reqwest::Client::new();
-    let post_body = json!({
-        "username": username,
-        "password": password
-    });
-    let post_body_str = post_body.to_string();
-    client
-        .post(format!(
-            "{}://{}/login",
-            env::var("PROTOCOL").unwrap(),
-            env::var("HOST").unwrap()
-        ))
-        .header(reqwest::header::CONTENT_TYPE, "application/json")
-        .body(post_body_str)
-        .send()
-        .await
-}
-
-async fn register_request(
-    username: &str,
-    email: &str,
-    password: &str,
-) -> Result<reqwest::Response, reqwest::Error> {
-    let client = reqwest::Client::new();
-    let post_body = json!({
-        "username": username,
-        "email": email,
-        "password": password
-    });
-    let post_body_str = post_body.to_string();
-    client
-        .post(format!(
-            "{}://{}/register",
-            env::var("PROTOCOL").unwrap(),
-            env::var("HOST").unwrap()
-        ))
-        .header(reqwest::header::CONTENT_TYPE, "application/json")
-        .body(post_body_str)
-        .send()
-        .await
-}
-
-async fn login_prompt() {
-    let jwt_token: &awc::error::HeaderValue;
+async fn login_prompt(client: &mut Client, saved_username: Option<&str>) {
     let mut attempts: usize = 0;
 
     loop {
@@ -76,38 +32,40 @@ async fn login_prompt() {
             );
             process::exit(0);
         }
-        print!("Username >> ");
         let mut username = String::with_capacity(32);
-        let _ = io::stdout().flush();
-        if io::stdin().read_line(&mut username).is_err() {
-            return;
-        }
-        let username_newline_stripped = username.strip_suffix("\n").unwrap();
+        let username_newline_stripped = match saved_username {
+            Some(saved_username) => saved_username,
+            None => {
+                print!("Username >> ");
+                let _ = io::stdout().flush();
+                if io::stdin().read_line(&mut username).is_err() {
+                    return;
+                }
+                username.strip_suffix('\n').unwrap()
+            }
+        };
 
         let password = rpassword::prompt_password("Password >> ").unwrap();
 
-        match login_request(&username_newline_stripped, &password).await {
-            Ok(resp) => {
-                attempts += 1;
-                if resp.status() != 200 {
-                    println!("Something went wrong, check your input and try again");
+        attempts += 1;
+        match client.login(username_newline_stripped, &password).await {
+            Ok(_) => break,
+            Err(ClientError::Rejected(reason)) => {
+                println!("Something went wrong, check your input and try again");
 
-                    log::debug!("This is what went wrong: {:?}", resp.text().await);
-                    continue;
-                }
-                jwt_token = resp.headers().get("Authorization").unwrap();
-                env::set_var("TERMTALK_CLI_JWT_TOKEN", jwt_token.to_str().unwrap());
-                break;
+                log::debug!("This is what went wrong: {:?}", reason);
             }
-            Err(_) => {
-                attempts += 1;
-                log::error!("Something went wrong while trying to login. Please try again");
+            Err(e) => {
+                log::error!(
+                    "Something went wrong while trying to login. Please try again: {}",
+                    e
+                );
             }
         };
     }
 }
 
-async fn register_prompt() {
+async fn register_prompt(client: &Client) {
     println!("Input registration details >> ");
 
     let mut register_username = String::with_capacity(32);
@@ -130,22 +88,21 @@ async fn register_prompt() {
 
     let register_password = rpassword::prompt_password("Password >> ").unwrap();
 
-    let register_username_stripped = register_username.strip_suffix("\n").unwrap();
-    let register_email_stripped = register_email.strip_suffix("\n").unwrap();
+    let register_username_stripped = register_username.strip_suffix('\n').unwrap();
+    let register_email_stripped = register_email.strip_suffix('\n').unwrap();
 
-    match register_request(
-        &register_username_stripped,
-        &register_email_stripped,
-        &register_password,
-    )
-    .await
+    match client
+        .register(
+            register_username_stripped,
+            register_email_stripped,
+            &register_password,
+        )
+        .await
     {
-        Ok(resp) => {
-            if resp.status() != 201 {
-                println!("Registration failed. termtalk-cli will exit now");
-                process::exit(0);
-            }
-            println!("User {} with email {} was successfully registered. Make sure to login to use termtalk-cli", register_username_stripped, register_email_stripped);
+        Ok(()) => println!("User {} with email {} was successfully registered. Make sure to login to use termtalk-cli", register_username_stripped, register_email_stripped),
+        Err(ClientError::Rejected(reason)) => {
+            println!("Registration failed because {}. termtalk-cli will exit now", reason);
+            process::exit(0);
         }
         Err(e) => println!("e: {:?}", e),
     };
@@ -157,19 +114,211 @@ fn set_default_env_vars() {
         Err(_) => env::set_var("RUST_LOG", "info"),
     };
 
+    match env::var("PROTOCOL") {
+        Ok(_) => {}
+        Err(_) => env::set_var("PROTOCOL", "http"),
+    };
+
     match env::var("HOST") {
         Ok(_) => {}
-        Err(_) => env::set_var("HOST", "http://localhost:8080"),
+        Err(_) => env::set_var("HOST", "localhost:8080"),
     };
 }
 
+#[derive(Parser)]
+#[command(name = "termtalk-cli", about = "Chat on a termtalk-api server")]
+struct Cli {
+    #[arg(
+        long,
+        help = "Use the line based interface instead of the full-screen UI"
+    )]
+    plain: bool,
+    #[arg(long, global = true, help = "Print output as JSON lines")]
+    json: bool,
+    #[arg(
+        long,
+        global = true,
+        env = "TERMTALK_PROFILE",
+        default_value = "default",
+        help = "Server profile to use"
+    )]
+    profile: String,
+    #[arg(long, global = true, help = "Read the login token from this file")]
+    token_file: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "Send a message to a room, or one message per stdin line")]
+    Send {
+        #[arg(long)]
+        room: String,
+        message: Vec<String>,
+    },
+    #[command(about = "Follow the messages of a room")]
+    Tail {
+        #[arg(long)]
+        room: String,
+    },
+}
+
 #[actix_web::main]
 async fn main() {
     dotenv::dotenv().ok();
     set_default_env_vars();
     env_logger::init();
 
+    let cli = Cli::parse();
+    if !config::is_valid_profile_name(&cli.profile) {
+        eprintln!(
+            "Profile names may only contain letters, digits, '-' and '_', got {}",
+            cli.profile
+        );
+        process::exit(1);
+    }
+    let profile = ActiveProfile::resolve(
+        &cli.profile,
+        &env::var("PROTOCOL").unwrap(),
+        &env::var("HOST").unwrap(),
+    );
+    let client = Client::new(&profile.profile.protocol, &profile.profile.host);
+
+    match cli.command {
+        Some(command) => run_script(client, profile, command, cli.token_file, cli.json).await,
+        None => run_interactive(client, profile, cli.plain).await,
+    }
+}
+
+fn remember_login(client: &Client, profile: &mut ActiveProfile) {
+    if let (Some(username), Some(token)) = (client.username(), client.token()) {
+        if let Err(e) = profile.remember(username, token, client.refresh_token()) {
+            log::warn!(
+                "Could not save the login for profile {}: {}",
+                profile.name,
+                e
+            );
+        }
+    }
+}
+
+async fn run_script(
+    client: Client,
+    profile: ActiveProfile,
+    command: Command,
+    token_file: Option<PathBuf>,
+    json: bool,
+) {
+    let saved_token = profile.saved_token();
+    let result = match script::authenticate(client, token_file.as_deref(), saved_token).await {
+        Ok(client) => match command {
+            Command::Send { room, message } => {
+                let message = match message.is_empty() {
+                    true => None,
+                    false => Some(message.join(" ")),
+                };
+                script::send(&client, &room, message, json).await
+            }
+            Command::Tail { room } => script::tail(&client, &room, json).await,
+        },
+        Err(e) => Err(e),
+    };
+
+    if let Err(message) = result {
+        if json {
+            println!(
+                "{}",
+                serde_json::json!({ "type": "error", "message": message })
+            );
+        } else {
+            eprintln!("{}", message);
+        }
+        process::exit(1);
+    }
+}
+
+async fn run_interactive(mut client: Client, mut profile: ActiveProfile, plain: bool) {
     println!("termtalk-cli has started\n\n");
+
+    let mut connection = None;
+    let token_updates = client.token_updates();
+    match (profile.profile.username.clone(), profile.saved_token()) {
+        (Some(username), Some(token)) => {
+            client = client.with_username(&username).with_token(&token);
+            if let Some(refresh_token) = profile.saved_refresh_token() {
+                client = client.with_refresh_token(&refresh_token);
+            }
+            let mut resumed = client.connect_with_reconnect(Reconnect::default()).await;
+            if matches!(resumed, Err(ClientError::Unauthorized)) && client.refresh().await.is_ok() {
+                resumed = client.connect_with_reconnect(Reconnect::default()).await;
+            }
+            match resumed {
+                Ok(resumed) => connection = Some(resumed),
+                Err(ClientError::Unauthorized) => {
+                    println!(
+                        "The saved login for {} has expired, enter the password to log in again",
+                        username
+                    );
+                    if let Err(e) = profile.forget_token() {
+                        log::warn!("Could not remove the saved token: {}", e);
+                    }
+                    login_prompt(&mut client, Some(&username)).await;
+                    remember_login(&client, &mut profile);
+                }
+                Err(e) => {
+                    println!("{}. Exiting now", e);
+                    process::exit(0);
+                }
+            }
+        }
+        _ => {
+            login_menu(&mut client).await;
+            remember_login(&client, &mut profile);
+        }
+    }
+
+    tokio::task::spawn_local(save_token_updates(profile, token_updates));
+
+    let connection = match connection {
+        Some(connection) => connection,
+        None => match client.connect_with_reconnect(Reconnect::default()).await {
+            Ok(connection) => connection,
+            Err(e) => {
+                println!("{}. Exiting now", e);
+                process::exit(0);
+            }
+        },
+    };
+    if plain {
+        run_plain(connection).await;
+        return;
+    }
+
+    let username = client.username().unwrap_or_default().to_owned();
+    match tui::run(connection, &username).await {
+        Ok(Some(reason)) => println!("{}", format!("{}\nWill exit now", reason).green()),
+        Ok(None) => {}
+        Err(e) => println!("The terminal UI failed: {:?}", e),
+    }
+}
+
+async fn save_token_updates(
+    profile: ActiveProfile,
+    mut token_updates: mpsc::UnboundedReceiver<TokenUpdate>,
+) {
+    while let Some((token, refresh_token)) = token_updates.recv().await {
+        if let Err(e) = profile.save_tokens(&token, refresh_token.as_deref()) {
+            log::warn!(
+                "Could not save the refreshed login for profile {}: {}",
+                profile.name,
+                e
+            );
+        }
+    }
+}
+
+async fn login_menu(client: &mut Client) {
     loop {
         println!("What would you like to do?:\n1. Login\n2. Register");
         let mut option = String::with_capacity(32);
@@ -177,40 +326,36 @@ async fn main() {
             return;
         }
 
-        let option_input = option.strip_suffix("\n").unwrap();
+        let option_input = option.strip_suffix('\n').unwrap();
         match option_input {
             "1" => {
-                login_prompt().await;
+                login_prompt(client, None).await;
                 break;
             }
             "2" => {
-                register_prompt().await;
+                register_prompt(client).await;
             }
             val => println!("{} is not a valid input, try again", val),
         };
     }
+}
+
+fn print_line(line: RenderedLine) {
+    let colored_line = line.text.color(line.color);
+    if line.dimmed {
+        println!("{}", colored_line.dimmed());
+    } else {
+        println!("{}", colored_line);
+    }
+}
 
+async fn run_plain(mut connection: Connection) {
     let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
     let mut cmd_rx = UnboundedReceiverStream::new(cmd_rx);
 
-    let (resp, mut ws) = awc::Client::new()
-        .ws(format!("ws://{}/connect", env::var("HOST").unwrap()))
-        .set_header(
-            "Authorization",
-            format!("Bearer {}", env::var("TERMTALK_CLI_JWT_TOKEN").unwrap()),
-        )
-        .connect()
-        .await
-        .unwrap();
-
-    if resp.status() != 101 {
-        println!("Could not connect to termtalk-api server. Exiting now");
-        process::exit(0);
-    }
     println!("{}", "Successfully connected to the termtalk-api\n".green());
 
-    println!("Choose from one of the following commands:\n\n/whoami - username and current channel\n/r /rooms - list existing rooms\n/o /online - which users are online\n/d /direct (user)\n/j /join (room)\n/h /here - list users in current room\n/w /whisper (user)\n/h /help - view this list of commands\n/e /exit - quit termtalk-cli\n\n");
-    println!("You are currently in the ");
+    println!("{}", HELP);
 
     // run blocking terminal input reader on separate thread
     thread::spawn(move || loop {
@@ -221,48 +366,97 @@ async fn main() {
             return;
         }
 
-        match cmd.as_str().strip_suffix("\n").unwrap() {
-            "/u" | "/users" => {
-                println!("{}", "Users Online:".green());
-            }
-            _ => {}
-        }
         cmd_tx.send(cmd).unwrap();
     });
 
+    let mut current_channel = String::new();
     loop {
         select! {
-            Some(msg) = ws.next() => {
-                match msg {
-                    Ok(ws::Frame::Text(txt)) => {
-                        let txt_from_bytes: &str = str::from_utf8(&txt).unwrap();
-                        let msg: Message = serde_json::from_str(txt_from_bytes).unwrap();
-                        println!("{}", msg.text.color(msg.color));
-                    }
-
-                    Ok(ws::Frame::Ping(_)) => {
-                        ws.send(ws::Message::Pong(Bytes::new())).await.unwrap();
+            Some(event) = connection.next() => {
+                match event {
+                    Ok(event) => {
+                        for line in render_event(event, &mut current_channel) {
+                            print_line(line);
+                        }
                     }
-                    Ok(ws::Frame::Close(close_reason)) => {
-                        let unwrapped_close_msg: String = close_reason.unwrap().description.unwrap();
-                        println!("{}", format!("Server closed connection because {}\nWill exit now", unwrapped_close_msg).green());
+                    Err(e) if e.is_reconnecting() => println!("{}", e.to_string().green()),
+                    Err(e) => {
+                        println!("{}", format!("{}\nWill exit now", e).green());
                         process::exit(0);
                     }
-
-                    _ => {}
                 }
             }
 
             Some(cmd) = cmd_rx.next() => {
-                let cmd_stripped = cmd.strip_suffix("\n").unwrap();
-                if cmd_stripped.is_empty() {
-                    continue;
+                let cmd_stripped = cmd.trim();
+                match cmd_stripped {
+                    "" => continue,
+                    "/help" => {
+                        println!("{}", HELP);
+                        continue;
+                    }
+                    "/e" | "/exit" => process::exit(0),
+                    _ => {}
                 }
 
-                ws.send(ws::Message::Text(cmd_stripped.into())).await.unwrap();
+                match ClientCommand::parse_text(cmd_stripped) {
+                    Ok(command) => {
+                        if let Err(e) = connection.send(command) {
+                            println!("{}", format!("{}\nWill exit now", e).green());
+                            process::exit(0);
+                        }
+                    }
+                    Err(e) => println!("{}", e.to_string().green()),
+                }
             }
 
             else => {}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script_commands() {
+        let cli = Cli::try_parse_from([
+            "termtalk-cli",
+            "send",
+            "--room",
+            "deploys",
+            "build",
+            "123",
+            "green",
+            "--json",
+        ])
+        .unwrap();
+        assert!(cli.json);
+        match cli.command {
+            Some(Command::Send { room, message }) => {
+                assert_eq!("deploys", room);
+                assert_eq!(vec!["build", "123", "green"], message);
+            }
+            _ => panic!("expected send command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "termtalk-cli",
+            "--token-file",
+            "token",
+            "tail",
+            "--room",
+            "Main",
+        ])
+        .unwrap();
+        assert_eq!(Some(PathBuf::from("token")), cli.token_file);
+        assert!(matches!(cli.command, Some(Command::Tail { room }) if room == "Main"));
+
+        assert!(Cli::try_parse_from(["termtalk-cli", "send", "hello"]).is_err());
+        assert!(Cli::try_parse_from(["termtalk-cli"])
+            .unwrap()
+            .command
+            .is_none());
+    }
+}
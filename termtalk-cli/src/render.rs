@@ -0,0 +1,185 @@
+use termtalk_proto::chat::{ChatType, MessageType};
+use termtalk_proto::direct_chats::DIRECT_CHANNEL_SEPARATOR;
+use termtalk_proto::protocol::ServerEvent;
+
+pub static HELP: &str = "Choose from one of the following commands:\n\n/whoami - username and current channel\n/r /rooms - list existing rooms\n/o /online - which users are online\n/d /direct (user)\n/dms - list recent direct chats with unread counts\n/j /join (room) [invite code]\n/room create (room) [private] | invite (room) | rename (room) (new name) | archive (room) | delete (room) | promote (room) (user) | demote (room) (user)\n/kick /ban /unban /mute /unmute (user) - moderate the current room\n/h /here - list users in current room\n/w /whisper (user)\n/s /search (query) - search messages, e.g. from:alice in:Main before:2026-01-01\n/help - view this list of commands\n/e /exit - quit termtalk-cli\n\n";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedLine {
+    pub text: String,
+    pub color: &'static str,
+    pub dimmed: bool,
+}
+
+impl RenderedLine {
+    pub fn new(text: String, color: &'static str) -> Self {
+        RenderedLine {
+            text,
+            color,
+            dimmed: false,
+        }
+    }
+}
+
+pub fn select_color(msg_type: &MessageType) -> &'static str {
+    match msg_type {
+        MessageType::Direct => "blue",
+        MessageType::Room => "white",
+        MessageType::Whisper => "magenta",
+        MessageType::Server => "green",
+    }
+}
+
+fn notice(text: String) -> Vec<RenderedLine> {
+    vec![RenderedLine::new(text, "green")]
+}
+
+fn other_participant<'a>(channel: &'a str, username: &'a str) -> &'a str {
+    channel
+        .split(DIRECT_CHANNEL_SEPARATOR)
+        .find(|user| *user != username)
+        .unwrap_or(username)
+}
+
+pub fn render_event(event: ServerEvent, current_channel: &mut String) -> Vec<RenderedLine> {
+    match event {
+        ServerEvent::MessageReceived {
+            sender,
+            channel,
+            chat_type,
+            msg_type,
+            text,
+            history,
+            queued,
+            ..
+        } => {
+            let mut line = match chat_type {
+                ChatType::Whisper => format!("(whisper) {} {}", sender, text),
+                ChatType::Direct if channel != *current_channel => {
+                    format!("(direct) {}: {}", sender, text)
+                }
+                _ => format!("{}: {}", sender, text),
+            };
+            if queued {
+                line.push_str(" (queued)");
+            }
+            vec![RenderedLine {
+                text: line,
+                color: select_color(&msg_type),
+                dimmed: history,
+            }]
+        }
+        ServerEvent::Notice { text } => notice(text),
+        ServerEvent::UserJoined {
+            username,
+            channel,
+            chat_type,
+        } => match chat_type {
+            ChatType::Direct => notice(format!(
+                "User {} is direct chatting with {}",
+                username,
+                other_participant(&channel, &username)
+            )),
+            _ => notice(format!("User {} connected to room {}", username, channel)),
+        },
+        ServerEvent::UserLeft {
+            username,
+            channel,
+            chat_type,
+        } => match chat_type {
+            ChatType::Direct => notice(format!("User {} left direct chat", username)),
+            _ => notice(format!("User {} left room {}", username, channel)),
+        },
+        ServerEvent::ChannelJoined { channel, .. } => {
+            *current_channel = channel;
+            vec![]
+        }
+        ServerEvent::Identity {
+            username,
+            channel,
+            chat_type,
+        } => {
+            let whereami = match chat_type {
+                ChatType::Direct => format!(
+                    "directly messaging {}",
+                    other_participant(&channel, &username)
+                ),
+                _ => format!("in room {}", channel),
+            };
+            notice(format!(
+                "You are {} and you are currently {}",
+                username, whereami
+            ))
+        }
+        ServerEvent::UserList { channel, users } => {
+            let mut lines = match channel {
+                Some(_) => notice("List of who is here".to_owned()),
+                None => notice("Users currently online".to_owned()),
+            };
+            lines.extend(
+                users
+                    .into_iter()
+                    .map(|user| RenderedLine::new(user, "green")),
+            );
+            lines
+        }
+        ServerEvent::RoomList { rooms } => {
+            let mut lines = notice("List of Existing Rooms".to_owned());
+            lines.extend(rooms.into_iter().map(|room| {
+                let mut details = vec![format!("{} online", room.online_users)];
+                if room.private {
+                    details.push("private".to_owned());
+                }
+                if room.archived {
+                    details.push("archived".to_owned());
+                }
+                RenderedLine::new(format!("{} ({})", room.name, details.join(", ")), "green")
+            }));
+            lines
+        }
+        ServerEvent::DirectChatList { direct_chats } => {
+            if direct_chats.is_empty() {
+                return notice("No direct chats yet. Start one with /direct (user)".to_owned());
+            }
+            let mut lines = notice("Recent Direct Chats".to_owned());
+            lines.extend(direct_chats.into_iter().map(|direct_chat| {
+                if direct_chat.unread > 0 {
+                    RenderedLine::new(
+                        format!("{} ({} unread)", direct_chat.with, direct_chat.unread),
+                        "blue",
+                    )
+                } else {
+                    RenderedLine::new(direct_chat.with, "blue")
+                }
+            }));
+            lines
+        }
+        ServerEvent::SearchResults { query, hits } => {
+            if hits.is_empty() {
+                return notice(format!("No messages matched {}", query));
+            }
+            let mut lines = notice(format!("Search results for {}", query));
+            lines.extend(hits.into_iter().map(|hit| {
+                RenderedLine::new(
+                    format!("[{}] {}: {}", hit.channel, hit.sender, hit.msg),
+                    select_color(&hit.msg_type),
+                )
+            }));
+            lines
+        }
+        ServerEvent::RoomInvite {
+            room,
+            code,
+            expires_in,
+        } => notice(format!(
+            "Invite code for room {}: {}. It can be used once within {} hours with /join {} {}",
+            room,
+            code,
+            expires_in / 3600,
+            room,
+            code
+        )),
+        ServerEvent::Error { message, .. } => notice(message),
+        ServerEvent::Ack { .. } => vec![],
+    }
+}
@@ -0,0 +1,180 @@
+use crate::render::render_event;
+use futures_util::StreamExt as _;
+use std::collections::HashSet;
+use std::path::Path;
+use std::{env, fs, io, thread};
+use termtalk_client::client::Client;
+use termtalk_client::connection::{Connection, Reconnect};
+use termtalk_client::error::ClientError;
+use termtalk_proto::chat::ChatType;
+use termtalk_proto::protocol::ServerEvent;
+use tokio::{select, sync::mpsc};
+
+static TOKEN_ENV: &str = "TERMTALK_TOKEN";
+
+static USERNAME_ENV: &str = "TERMTALK_USERNAME";
+
+static PASSWORD_ENV: &str = "TERMTALK_PASSWORD";
+
+pub async fn authenticate(
+    client: Client,
+    token_file: Option<&Path>,
+    saved_token: Option<String>,
+) -> Result<Client, String> {
+    if let Some(token_file) = token_file {
+        let token = fs::read_to_string(token_file)
+            .map_err(|e| format!("Could not read token file {}: {}", token_file.display(), e))?;
+        return Ok(client.with_token(token.trim()));
+    }
+
+    if let Ok(token) = env::var(TOKEN_ENV) {
+        return Ok(client.with_token(token.trim()));
+    }
+
+    match (env::var(USERNAME_ENV), env::var(PASSWORD_ENV)) {
+        (Ok(username), Ok(password)) => {
+            let mut client = client;
+            client
+                .login(&username, &password)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(client)
+        }
+        _ => match saved_token {
+            Some(token) => Ok(client.with_token(&token)),
+            None => Err(format!(
+                "No credentials found. Pass --token-file, set {} or both {} and {}, or log in interactively to save a token",
+                TOKEN_ENV, USERNAME_ENV, PASSWORD_ENV
+            )),
+        },
+    }
+}
+
+fn print_event(event: ServerEvent, json: bool, current_channel: &mut String) {
+    if json {
+        println!("{}", serde_json::to_string(&event).unwrap());
+        return;
+    }
+    for line in render_event(event, current_channel) {
+        println!("{}", line.text);
+    }
+}
+
+async fn join(connection: &mut Connection, room: &str) -> Result<(), String> {
+    let join_id = connection
+        .join_room(room, None)
+        .map_err(|e| e.to_string())?;
+
+    while let Some(event) = connection.next().await {
+        match event.map_err(|e| e.to_string())? {
+            ServerEvent::Ack { id } if id == join_id => return Ok(()),
+            ServerEvent::Error { id, message, .. } if id == Some(join_id) => return Err(message),
+            _ => {}
+        }
+    }
+    Err(ClientError::Closed(None).to_string())
+}
+
+pub async fn send(
+    client: &Client,
+    room: &str,
+    message: Option<String>,
+    json: bool,
+) -> Result<(), String> {
+    let mut connection = client.connect().await.map_err(|e| e.to_string())?;
+    join(&mut connection, room).await?;
+
+    let (line_tx, mut line_rx) = mpsc::unbounded_channel();
+    match message {
+        Some(message) => {
+            line_tx.send(message).unwrap();
+            drop(line_tx);
+        }
+        None => {
+            thread::spawn(move || {
+                for line in io::stdin().lines() {
+                    match line {
+                        Ok(line) => {
+                            if line_tx.send(line).is_err() {
+                                return;
+                            }
+                        }
+                        Err(_) => return,
+                    }
+                }
+            });
+        }
+    }
+    let mut pending: HashSet<u64> = HashSet::new();
+    let mut input_done = false;
+    loop {
+        if input_done && pending.is_empty() {
+            return Ok(());
+        }
+
+        select! {
+            line = line_rx.recv(), if !input_done => match line {
+                Some(line) => {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let id = connection.send_message(&line).map_err(|e| e.to_string())?;
+                    pending.insert(id);
+                }
+                None => input_done = true,
+            },
+            event = connection.next() => match event {
+                Some(Ok(ServerEvent::Ack { id })) => {
+                    if pending.remove(&id) && json {
+                        print_event(ServerEvent::Ack { id }, json, &mut String::new());
+                    }
+                }
+                Some(Ok(ServerEvent::Error { message, .. })) => return Err(message),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.to_string()),
+                None => return Err(ClientError::Closed(None).to_string()),
+            },
+        }
+    }
+}
+
+pub async fn tail(client: &Client, room: &str, json: bool) -> Result<(), String> {
+    let mut connection = client
+        .connect_with_reconnect(Reconnect::default())
+        .await
+        .map_err(|e| e.to_string())?;
+    connection
+        .join_room(room, None)
+        .map_err(|e| e.to_string())?;
+
+    let mut current_channel = String::new();
+    let mut joined = false;
+    while let Some(event) = connection.next().await {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) if e.is_reconnecting() => {
+                eprintln!("{}", e);
+                continue;
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+        match &event {
+            ServerEvent::ChannelJoined { channel, chat_type } => {
+                joined = *chat_type == ChatType::Room && channel == room;
+                current_channel = channel.clone();
+                continue;
+            }
+            ServerEvent::Ack { .. } => continue,
+            ServerEvent::Error {
+                id: Some(_),
+                message,
+                ..
+            } if !joined => return Err(message.clone()),
+            _ => {}
+        }
+        if joined {
+            print_event(event, json, &mut current_channel);
+        }
+    }
+    Err(ClientError::Closed(None).to_string())
+}
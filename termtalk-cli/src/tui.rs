@@ -0,0 +1,523 @@
+use crate::render::{render_event, RenderedLine, HELP};
+use futures_util::StreamExt as _;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
+use ratatui::Frame;
+use std::time::Duration;
+use std::{io, thread};
+use termtalk_client::connection::Connection;
+use termtalk_proto::chat::ChatType;
+use termtalk_proto::direct_chats::DIRECT_CHANNEL_SEPARATOR;
+use termtalk_proto::protocol::{ClientCommand, ServerEvent};
+use termtalk_proto::rooms::RoomSummary;
+use tokio::{select, sync::mpsc};
+
+static MAX_PANE_LINES: usize = 1000;
+
+static MAX_INPUT_HISTORY: usize = 100;
+
+static SIDEBAR_WIDTH: u16 = 28;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Default)]
+struct PendingRefresh {
+    rooms: usize,
+    online: usize,
+    here: usize,
+    identity: usize,
+}
+
+#[derive(Default)]
+pub struct App {
+    username: String,
+    channel: String,
+    chat_type: Option<ChatType>,
+    lines: Vec<RenderedLine>,
+    scroll: usize,
+    input: Vec<char>,
+    cursor: usize,
+    history: Vec<String>,
+    history_index: Option<usize>,
+    rooms: Vec<RoomSummary>,
+    users_online: Vec<String>,
+    users_here: Vec<String>,
+    pending: PendingRefresh,
+    rejoining: bool,
+    should_quit: bool,
+}
+
+impl App {
+    pub fn new(username: &str) -> Self {
+        App {
+            username: username.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn push_lines(&mut self, lines: Vec<RenderedLine>) {
+        if self.scroll > 0 {
+            self.scroll += lines.len();
+        }
+        self.lines.extend(lines);
+        if self.lines.len() > MAX_PANE_LINES {
+            let overflow = self.lines.len() - MAX_PANE_LINES;
+            self.lines.drain(..overflow);
+            self.scroll = self.scroll.min(self.lines.len());
+        }
+    }
+
+    fn refresh(&mut self, connection: &mut Connection) {
+        for command in [
+            ClientCommand::ListRooms,
+            ClientCommand::ListUsersOnline,
+            ClientCommand::ListUsersInRoom,
+        ] {
+            if connection.send(command.clone()).is_ok() {
+                match command {
+                    ClientCommand::ListRooms => self.pending.rooms += 1,
+                    ClientCommand::ListUsersOnline => self.pending.online += 1,
+                    _ => self.pending.here += 1,
+                }
+            }
+        }
+    }
+
+    fn identify(&mut self, connection: &mut Connection) {
+        if connection.send(ClientCommand::WhoAmI).is_ok() {
+            self.pending.identity += 1;
+        }
+    }
+
+    fn handle_server_event(&mut self, event: ServerEvent, connection: &mut Connection) {
+        match &event {
+            ServerEvent::RoomList { rooms } => {
+                self.rooms = rooms.clone();
+                if self.pending.rooms > 0 {
+                    self.pending.rooms -= 1;
+                    return;
+                }
+            }
+            ServerEvent::UserList {
+                channel: None,
+                users,
+            } => {
+                self.users_online = users.clone();
+                if self.pending.online > 0 {
+                    self.pending.online -= 1;
+                    return;
+                }
+            }
+            ServerEvent::UserList {
+                channel: Some(_),
+                users,
+            } => {
+                self.users_here = users.clone();
+                if self.pending.here > 0 {
+                    self.pending.here -= 1;
+                    return;
+                }
+            }
+            ServerEvent::ChannelJoined { channel, chat_type } => {
+                self.channel = channel.clone();
+                self.chat_type = Some(chat_type.clone());
+                self.refresh(connection);
+                if self.rejoining {
+                    self.rejoining = false;
+                    self.identify(connection);
+                }
+            }
+            ServerEvent::Identity {
+                username,
+                channel,
+                chat_type,
+            } => {
+                self.username = username.clone();
+                self.channel = channel.clone();
+                self.chat_type = Some(chat_type.clone());
+                if self.pending.identity > 0 {
+                    self.pending.identity -= 1;
+                    return;
+                }
+            }
+            ServerEvent::UserJoined { .. } | ServerEvent::UserLeft { .. } => {
+                self.refresh(connection)
+            }
+            _ => {}
+        }
+
+        let mut current_channel = self.channel.clone();
+        let lines = render_event(event, &mut current_channel);
+        self.push_lines(lines);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<String> {
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Char('c') | KeyCode::Char('d')
+                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.should_quit = true;
+            }
+            KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => self.cursor = 0,
+            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.cursor = self.input.len()
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input.drain(..self.cursor);
+                self.cursor = 0;
+            }
+            KeyCode::Char(c) => {
+                self.input.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.input.remove(self.cursor);
+            }
+            KeyCode::Delete if self.cursor < self.input.len() => {
+                self.input.remove(self.cursor);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.input.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.input.len(),
+            KeyCode::Up => self.recall_history(true),
+            KeyCode::Down => self.recall_history(false),
+            KeyCode::PageUp => self.scroll = (self.scroll + 10).min(self.lines.len()),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::Enter => {
+                let line: String = self.input.drain(..).collect();
+                self.cursor = 0;
+                self.history_index = None;
+                let line = line.trim().to_owned();
+                if line.is_empty() {
+                    return None;
+                }
+                if self.history.last() != Some(&line) {
+                    self.history.push(line.clone());
+                    if self.history.len() > MAX_INPUT_HISTORY {
+                        self.history.remove(0);
+                    }
+                }
+                self.scroll = 0;
+                return Some(line);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn recall_history(&mut self, older: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match (self.history_index, older) {
+            (None, true) => Some(self.history.len() - 1),
+            (None, false) => None,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index + 1 < self.history.len() => Some(index + 1),
+            (Some(_), false) => None,
+        };
+        self.history_index = index;
+        self.input = match index {
+            Some(index) => self.history[index].chars().collect(),
+            None => vec![],
+        };
+        self.cursor = self.input.len();
+    }
+
+    fn submit(&mut self, line: String, connection: &mut Connection) {
+        match line.as_str() {
+            "/help" => {
+                let lines = HELP
+                    .lines()
+                    .map(|line| RenderedLine::new(line.to_owned(), "green"))
+                    .collect();
+                self.push_lines(lines);
+                return;
+            }
+            "/e" | "/exit" => {
+                self.should_quit = true;
+                return;
+            }
+            _ => {}
+        }
+
+        match ClientCommand::parse_text(&line) {
+            Ok(command) => {
+                if let Err(e) = connection.send(command) {
+                    self.push_lines(vec![RenderedLine::new(e.to_string(), "green")]);
+                }
+            }
+            Err(e) => self.push_lines(vec![RenderedLine::new(e.to_string(), "green")]),
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+            ])
+            .split(frame.area());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(20), Constraint::Length(SIDEBAR_WIDTH)])
+            .split(rows[0]);
+
+        self.draw_messages(frame, columns[0]);
+        self.draw_sidebar(frame, columns[1]);
+        self.draw_input(frame, rows[1]);
+        self.draw_status(frame, rows[2]);
+    }
+
+    fn draw_messages(&self, frame: &mut Frame, area: Rect) {
+        let title = match self.scroll {
+            0 => " Messages ".to_owned(),
+            scroll => format!(" Messages (scrolled up {}) ", scroll),
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let inner = block.inner(area);
+        let width = inner.width.max(1) as usize;
+        let height = inner.height as usize;
+
+        let end = self.lines.len() - self.scroll.min(self.lines.len());
+        let mut visible: Vec<Line> = vec![];
+        for line in self.lines[..end].iter().rev() {
+            let style = line_style(line);
+            let chars: Vec<char> = line.text.chars().collect();
+            let mut chunks: Vec<Line> = chars
+                .chunks(width)
+                .map(|chunk| Line::from(Span::styled(chunk.iter().collect::<String>(), style)))
+                .collect();
+            if chunks.is_empty() {
+                chunks.push(Line::from(""));
+            }
+            for chunk in chunks.into_iter().rev() {
+                visible.push(chunk);
+            }
+            if visible.len() >= height {
+                break;
+            }
+        }
+        visible.truncate(height);
+        visible.reverse();
+
+        frame.render_widget(Paragraph::new(visible).block(block), area);
+    }
+
+    fn draw_sidebar(&self, frame: &mut Frame, area: Rect) {
+        let sections = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(40),
+                Constraint::Percentage(30),
+                Constraint::Percentage(30),
+            ])
+            .split(area);
+
+        let rooms: Vec<ListItem> = self
+            .rooms
+            .iter()
+            .map(|room| {
+                let mut style = Style::default().fg(Color::Green);
+                if room.name == self.channel && self.chat_type == Some(ChatType::Room) {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+                }
+                if room.archived {
+                    style = style.add_modifier(Modifier::DIM);
+                }
+                let private = if room.private { " *" } else { "" };
+                ListItem::new(format!("{} ({}){}", room.name, room.online_users, private))
+                    .style(style)
+            })
+            .collect();
+        frame.render_widget(
+            List::new(rooms).block(Block::default().borders(Borders::ALL).title(" Rooms ")),
+            sections[0],
+        );
+        frame.render_widget(user_list(&self.users_online, " Online "), sections[1]);
+        frame.render_widget(user_list(&self.users_here, " Here "), sections[2]);
+    }
+
+    fn draw_input(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title(" > ");
+        let inner = block.inner(area);
+        let width = inner.width.max(1) as usize;
+        let offset = (self.cursor + 1).saturating_sub(width);
+        let text: String = self.input.iter().skip(offset).take(width).collect();
+
+        frame.render_widget(Paragraph::new(text).block(block), area);
+        frame.set_cursor_position((inner.x + (self.cursor - offset) as u16, inner.y));
+    }
+
+    fn draw_status(&self, frame: &mut Frame, area: Rect) {
+        let whereami = match &self.chat_type {
+            Some(ChatType::Direct) => format!(
+                "directly messaging {}",
+                self.channel
+                    .split(DIRECT_CHANNEL_SEPARATOR)
+                    .find(|user| *user != self.username)
+                    .unwrap_or(&self.username)
+            ),
+            Some(_) => format!("in room {}", self.channel),
+            None => "connecting".to_owned(),
+        };
+        let status = format!(
+            " {} | {} | {} online | /help for commands, Ctrl-C to quit",
+            self.username,
+            whereami,
+            self.users_online.len()
+        );
+        frame.render_widget(
+            Paragraph::new(status).style(Style::default().fg(Color::Black).bg(Color::Green)),
+            area,
+        );
+    }
+}
+
+fn line_style(line: &RenderedLine) -> Style {
+    let color = match line.color {
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "green" => Color::Green,
+        _ => Color::White,
+    };
+    let style = Style::default().fg(color);
+    if line.dimmed {
+        style.add_modifier(Modifier::DIM)
+    } else {
+        style
+    }
+}
+
+fn user_list<'a>(users: &'a [String], title: &'a str) -> List<'a> {
+    let items: Vec<ListItem> = users
+        .iter()
+        .map(|user| ListItem::new(user.as_str()).style(Style::default().fg(Color::Green)))
+        .collect();
+    List::new(items).block(Block::default().borders(Borders::ALL).title(title))
+}
+
+pub async fn run(mut connection: Connection, username: &str) -> io::Result<Option<String>> {
+    let (key_tx, mut key_rx) = mpsc::unbounded_channel();
+    thread::spawn(move || loop {
+        match event::read() {
+            Ok(Event::Key(key)) => {
+                if key_tx.send(key).is_err() {
+                    return;
+                }
+            }
+            Ok(_) => {}
+            Err(_) => return,
+        }
+    });
+
+    let mut terminal = ratatui::init();
+    let mut app = App::new(username);
+    let mut refresh = tokio::time::interval(REFRESH_INTERVAL);
+    let mut exit_reason = None;
+    app.identify(&mut connection);
+
+    while !app.should_quit {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        select! {
+            event = connection.next() => match event {
+                Some(Ok(event)) => app.handle_server_event(event, &mut connection),
+                Some(Err(e)) if e.is_reconnecting() => {
+                    app.chat_type = None;
+                    app.rejoining = true;
+                    app.push_lines(vec![RenderedLine::new(e.to_string(), "green")]);
+                }
+                Some(Err(e)) => {
+                    exit_reason = Some(e.to_string());
+                    break;
+                }
+                None => break,
+            },
+            Some(key) = key_rx.recv() => {
+                if let Some(line) = app.handle_key(key) {
+                    app.submit(line, &mut connection);
+                }
+            }
+            _ = refresh.tick() => app.refresh(&mut connection),
+        }
+    }
+
+    ratatui::restore();
+    Ok(exit_reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_input_editing_and_history() {
+        let mut app = App::new("alice");
+        for c in "helo".chars() {
+            app.handle_key(press(KeyCode::Char(c)));
+        }
+        app.handle_key(press(KeyCode::Left));
+        app.handle_key(press(KeyCode::Char('l')));
+        assert_eq!(
+            Some(String::from("hello")),
+            app.handle_key(press(KeyCode::Enter))
+        );
+        assert!(app.input.is_empty());
+
+        app.handle_key(press(KeyCode::Up));
+        assert_eq!("hello", app.input.iter().collect::<String>());
+        app.handle_key(press(KeyCode::Down));
+        assert!(app.input.is_empty());
+    }
+
+    #[test]
+    fn test_pane_keeps_most_recent_lines() {
+        let mut app = App::new("alice");
+        let lines = (0..MAX_PANE_LINES + 5)
+            .map(|i| RenderedLine::new(i.to_string(), "white"))
+            .collect();
+        app.push_lines(lines);
+
+        assert_eq!(MAX_PANE_LINES, app.lines.len());
+        assert_eq!("5", app.lines[0].text);
+    }
+
+    #[test]
+    fn test_draw_shows_sidebar_and_status() {
+        let mut app = App::new("alice");
+        app.channel = String::from("Main");
+        app.chat_type = Some(ChatType::Room);
+        app.users_online = vec![String::from("alice"), String::from("bob")];
+        app.push_lines(vec![RenderedLine::new("bob: ".repeat(40), "white")]);
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+
+        assert!(screen.contains("alice | in room Main | 2 online"));
+        assert!(screen.contains("Online"));
+    }
+}
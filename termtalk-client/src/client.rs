@@ -0,0 +1,239 @@
+use crate::connection::{Connection, Reconnect};
+use crate::error::ClientError;
+use serde_json::Value;
+use termtalk_proto::request_models::{LoginForm, RefreshForm, RegistrationForm};
+use tokio::sync::mpsc;
+
+pub type TokenUpdate = (String, Option<String>);
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    protocol: String,
+    host: String,
+    http: reqwest::Client,
+    username: Option<String>,
+    token: Option<String>,
+    refresh_token: Option<String>,
+    token_updates: Option<mpsc::UnboundedSender<TokenUpdate>>,
+}
+
+impl Client {
+    pub fn new(protocol: &str, host: &str) -> Self {
+        Client {
+            protocol: protocol.to_owned(),
+            host: host.to_owned(),
+            http: reqwest::Client::new(),
+            username: None,
+            token: None,
+            refresh_token: None,
+            token_updates: None,
+        }
+    }
+
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_owned());
+        self
+    }
+
+    pub fn with_refresh_token(mut self, refresh_token: &str) -> Self {
+        self.refresh_token = Some(refresh_token.to_owned());
+        self
+    }
+
+    pub fn token_updates(&mut self) -> mpsc::UnboundedReceiver<TokenUpdate> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.token_updates = Some(tx);
+        rx
+    }
+
+    pub fn with_username(mut self, username: &str) -> Self {
+        self.username = Some(username.to_owned());
+        self
+    }
+
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    pub fn refresh_token(&self) -> Option<&str> {
+        self.refresh_token.as_deref()
+    }
+
+    pub fn http_url(&self, path: &str) -> String {
+        format!("{}://{}{}", self.protocol, self.host, path)
+    }
+
+    pub fn ws_url(&self, path: &str) -> String {
+        let scheme = match self.protocol.as_str() {
+            "https" => "wss",
+            _ => "ws",
+        };
+        format!("{}://{}{}", scheme, self.host, path)
+    }
+
+    pub async fn register(
+        &self,
+        username: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<(), ClientError> {
+        let resp = self
+            .http
+            .post(self.http_url("/register"))
+            .json(&RegistrationForm {
+                username: username.to_owned(),
+                email: email.to_owned(),
+                password: password.to_owned(),
+            })
+            .send()
+            .await?;
+
+        if resp.status() != 201 {
+            return Err(Client::rejection(resp).await);
+        }
+        Ok(())
+    }
+
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<String, ClientError> {
+        let resp = self
+            .http
+            .post(self.http_url("/login"))
+            .json(&LoginForm {
+                username: username.to_owned(),
+                password: password.to_owned(),
+            })
+            .send()
+            .await?;
+
+        if resp.status() != 200 {
+            return Err(Client::rejection(resp).await);
+        }
+        let token = self.store_tokens(&resp)?;
+        self.username = Some(username.to_owned());
+        Ok(token)
+    }
+
+    pub async fn refresh(&mut self) -> Result<String, ClientError> {
+        let refresh_token = self
+            .refresh_token
+            .as_ref()
+            .ok_or(ClientError::NotLoggedIn)?;
+        let resp = self
+            .http
+            .post(self.http_url("/refresh"))
+            .json(&RefreshForm {
+                refresh_token: refresh_token.to_owned(),
+            })
+            .send()
+            .await?;
+
+        if resp.status() == 401 {
+            self.refresh_token = None;
+            return Err(ClientError::Unauthorized);
+        }
+        if resp.status() != 200 {
+            return Err(Client::rejection(resp).await);
+        }
+        self.store_tokens(&resp)
+    }
+
+    pub async fn logout(&mut self) -> Result<(), ClientError> {
+        let token = self.token.take().ok_or(ClientError::NotLoggedIn)?;
+        let request = self
+            .http
+            .post(self.http_url("/logout"))
+            .header("Authorization", format!("Bearer {}", token));
+        let request = match self.refresh_token.take() {
+            Some(refresh_token) => request.json(&RefreshForm { refresh_token }),
+            None => request,
+        };
+        let resp = request.send().await?;
+
+        if resp.status() != 200 {
+            return Err(Client::rejection(resp).await);
+        }
+        Ok(())
+    }
+
+    pub async fn logout_everywhere(&mut self) -> Result<(), ClientError> {
+        let token = self.token.take().ok_or(ClientError::NotLoggedIn)?;
+        self.refresh_token = None;
+        let resp = self
+            .http
+            .post(self.http_url("/logout/all"))
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await?;
+
+        if resp.status() != 200 {
+            return Err(Client::rejection(resp).await);
+        }
+        Ok(())
+    }
+
+    fn store_tokens(&mut self, resp: &reqwest::Response) -> Result<String, ClientError> {
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|val| val.to_str().ok())
+                .map(|val| val.to_owned())
+        };
+        let token = match header("Authorization") {
+            Some(token) => token,
+            None => {
+                return Err(ClientError::Rejected(
+                    "login response did not include a token".to_owned(),
+                ))
+            }
+        };
+
+        self.token = Some(token.clone());
+        self.refresh_token = header("Refresh-Token");
+        if let Some(token_updates) = &self.token_updates {
+            let _ = token_updates.send((token.clone(), self.refresh_token.clone()));
+        }
+        Ok(token)
+    }
+
+    pub async fn connect(&self) -> Result<Connection, ClientError> {
+        let token = self.token.as_ref().ok_or(ClientError::NotLoggedIn)?;
+        Connection::open(&self.ws_url("/connect"), token, None, None).await
+    }
+
+    pub async fn connect_with_reconnect(
+        &self,
+        reconnect: Reconnect,
+    ) -> Result<Connection, ClientError> {
+        let token = self.token.as_ref().ok_or(ClientError::NotLoggedIn)?;
+        let refresher = self.refresh_token.as_ref().map(|_| self.clone());
+        Connection::open(&self.ws_url("/connect"), token, Some(reconnect), refresher).await
+    }
+
+    async fn rejection(resp: reqwest::Response) -> ClientError {
+        let status = resp.status();
+        let reason = match resp.json::<Value>().await {
+            Ok(body) => body["data"].as_str().map(|data| data.to_owned()),
+            Err(_) => None,
+        };
+        ClientError::Rejected(reason.unwrap_or_else(|| status.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urls_follow_protocol() {
+        let client = Client::new("http", "localhost:8080");
+        assert_eq!("http://localhost:8080/login", client.http_url("/login"));
+        assert_eq!("ws://localhost:8080/connect", client.ws_url("/connect"));
+
+        let client = Client::new("https", "chat.example.com");
+        assert_eq!("wss://chat.example.com/connect", client.ws_url("/connect"));
+    }
+}
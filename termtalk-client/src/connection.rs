@@ -0,0 +1,637 @@
+use crate::client::Client;
+use crate::error::ClientError;
+use awc::error::WsClientError;
+use awc::http::StatusCode;
+use awc::ws::{self, CloseCode};
+use futures_util::{SinkExt as _, Stream, StreamExt as _};
+use std::collections::BTreeSet;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use termtalk_proto::chat::ChatType;
+use termtalk_proto::protocol::{
+    ClientCommand, ClientFrame, ServerEvent, ServerFrame, PROTOCOL_VERSION,
+};
+use tokio::{select, sync::mpsc};
+
+type Socket = actix_codec::Framed<awc::BoxedSocket, ws::Codec>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reconnect {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Reconnect {
+    fn default() -> Self {
+        Reconnect {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            max_attempts: None,
+        }
+    }
+}
+
+impl Reconnect {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+}
+
+enum Ended {
+    ByClient,
+    Fatal(Option<String>),
+    Lost(Option<String>),
+}
+
+#[derive(Default)]
+struct Resume {
+    direct_username: Option<String>,
+    channel: Option<String>,
+    rejoin: Option<ClientCommand>,
+    cursor: Option<u64>,
+    seen_at_cursor: BTreeSet<String>,
+    pending: Option<u64>,
+    resumed_from: BTreeSet<String>,
+    buffered: Vec<ServerEvent>,
+    next_id: u64,
+}
+
+impl Resume {
+    fn new() -> Self {
+        Resume {
+            next_id: u64::MAX,
+            ..Default::default()
+        }
+    }
+
+    fn track_command(&mut self, command: &ClientCommand) {
+        if let ClientCommand::JoinDirect { username, .. } = command {
+            self.direct_username = Some(username.clone());
+        }
+    }
+
+    fn track_event(&mut self, event: &ServerEvent) {
+        if let ServerEvent::MessageReceived {
+            channel,
+            chat_type,
+            message_id: Some(message_id),
+            created_at: Some(created_at),
+            ..
+        } = event
+        {
+            if *chat_type != ChatType::Whisper && self.channel.as_ref() == Some(channel) {
+                self.advance_cursor(message_id, *created_at);
+            }
+        }
+        if let ServerEvent::ChannelJoined { channel, chat_type } = event {
+            if self.channel.as_ref() != Some(channel) {
+                self.channel = Some(channel.clone());
+                self.cursor = None;
+                self.seen_at_cursor.clear();
+            }
+            self.rejoin = match chat_type {
+                ChatType::Room => Some(ClientCommand::JoinRoom {
+                    room: channel.clone(),
+                    invite: None,
+                    since: None,
+                }),
+                ChatType::Direct => {
+                    self.direct_username
+                        .clone()
+                        .map(|username| ClientCommand::JoinDirect {
+                            username,
+                            since: None,
+                        })
+                }
+                _ => self.rejoin.take(),
+            };
+        }
+    }
+
+    fn advance_cursor(&mut self, message_id: &str, created_at: u64) {
+        match self.cursor {
+            Some(cursor) if created_at < cursor => {}
+            Some(cursor) if created_at == cursor => {
+                self.seen_at_cursor.insert(message_id.to_owned());
+            }
+            _ => {
+                self.cursor = Some(created_at);
+                self.seen_at_cursor = BTreeSet::from([message_id.to_owned()]);
+            }
+        }
+    }
+
+    fn resume_frame(&mut self) -> Option<ClientFrame> {
+        let since = self.cursor;
+        let command = match self.rejoin.clone()? {
+            ClientCommand::JoinRoom { room, invite, .. } => ClientCommand::JoinRoom {
+                room,
+                invite,
+                since,
+            },
+            ClientCommand::JoinDirect { username, .. } => {
+                ClientCommand::JoinDirect { username, since }
+            }
+            command => command,
+        };
+        let id = self.next_id;
+        self.next_id -= 1;
+        self.pending = Some(id);
+        self.resumed_from = self.seen_at_cursor.clone();
+        self.buffered.clear();
+        Some(ClientFrame {
+            v: PROTOCOL_VERSION,
+            id: Some(id),
+            command,
+        })
+    }
+
+    fn receive(&mut self, event: ServerEvent) -> Vec<ServerEvent> {
+        let pending = match self.pending {
+            Some(pending) => pending,
+            None => return vec![event],
+        };
+        match event {
+            ServerEvent::Ack { id } if id == pending => {
+                self.pending = None;
+                let buffered = std::mem::take(&mut self.buffered);
+                let resumed_at = buffered
+                    .iter()
+                    .rposition(|event| matches!(event, ServerEvent::ChannelJoined { .. }))
+                    .unwrap_or(0);
+                let mut delivered = std::mem::take(&mut self.resumed_from);
+                buffered
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(index, event)| *index >= resumed_at || !Resume::replaced(event))
+                    .map(|(_, event)| event)
+                    .filter(|event| match event {
+                        ServerEvent::MessageReceived {
+                            message_id: Some(message_id),
+                            ..
+                        } => delivered.insert(message_id.clone()),
+                        _ => true,
+                    })
+                    .collect()
+            }
+            ServerEvent::Error { id: Some(id), .. } if id == pending => {
+                self.pending = None;
+                let mut events = std::mem::take(&mut self.buffered);
+                events.push(event);
+                events
+            }
+            event => {
+                self.buffered.push(event);
+                vec![]
+            }
+        }
+    }
+
+    fn replaced(event: &ServerEvent) -> bool {
+        matches!(
+            event,
+            ServerEvent::ChannelJoined { .. } | ServerEvent::MessageReceived { history: true, .. }
+        )
+    }
+}
+
+pub struct Connection {
+    commands: mpsc::UnboundedSender<ClientFrame>,
+    events: mpsc::UnboundedReceiver<Result<ServerEvent, ClientError>>,
+    next_id: u64,
+}
+
+impl Connection {
+    pub(crate) async fn open(
+        url: &str,
+        token: &str,
+        reconnect: Option<Reconnect>,
+        refresher: Option<Client>,
+    ) -> Result<Connection, ClientError> {
+        let ws = Connection::handshake(url, token).await?;
+
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        tokio::task::spawn_local(Connection::run(
+            url.to_owned(),
+            token.to_owned(),
+            reconnect,
+            refresher,
+            ws,
+            command_rx,
+            event_tx,
+        ));
+
+        Ok(Connection {
+            commands: command_tx,
+            events: event_rx,
+            next_id: 1,
+        })
+    }
+
+    async fn handshake(url: &str, token: &str) -> Result<Socket, ClientError> {
+        let (resp, ws) = awc::Client::new()
+            .ws(url)
+            .set_header("Authorization", format!("Bearer {}", token))
+            .connect()
+            .await
+            .map_err(|error| match error {
+                WsClientError::InvalidResponseStatus(status)
+                    if status == StatusCode::BAD_REQUEST || status == StatusCode::UNAUTHORIZED =>
+                {
+                    ClientError::Unauthorized
+                }
+                error => ClientError::Connect(error.to_string()),
+            })?;
+
+        if resp.status() != 101 {
+            return Err(ClientError::Connect(resp.status().to_string()));
+        }
+        Ok(ws)
+    }
+
+    async fn run(
+        url: String,
+        mut token: String,
+        reconnect: Option<Reconnect>,
+        mut refresher: Option<Client>,
+        mut ws: Socket,
+        mut commands: mpsc::UnboundedReceiver<ClientFrame>,
+        events: mpsc::UnboundedSender<Result<ServerEvent, ClientError>>,
+    ) {
+        let mut resume = Resume::new();
+        loop {
+            let mut reason =
+                match Connection::pump(&mut ws, &mut commands, &events, &mut resume).await {
+                    Ended::ByClient => return,
+                    Ended::Fatal(reason) => {
+                        let _ = events.send(Err(ClientError::Closed(reason)));
+                        return;
+                    }
+                    Ended::Lost(reason) => reason,
+                };
+            let reconnect = match &reconnect {
+                Some(reconnect) => reconnect,
+                None => {
+                    let _ = events.send(Err(ClientError::Closed(reason)));
+                    return;
+                }
+            };
+
+            let mut attempt = 0;
+            ws = loop {
+                attempt += 1;
+                if reconnect
+                    .max_attempts
+                    .is_some_and(|max_attempts| attempt > max_attempts)
+                {
+                    let _ = events.send(Err(ClientError::Closed(reason)));
+                    return;
+                }
+
+                let retry_in = reconnect.delay(attempt);
+                let disconnected = ClientError::Disconnected {
+                    reason: reason.clone(),
+                    attempt,
+                    retry_in,
+                };
+                if events.send(Err(disconnected)).is_err() {
+                    return;
+                }
+                tokio::time::sleep(retry_in).await;
+
+                let mut handshake = Connection::handshake(&url, &token).await;
+                if let (Err(ClientError::Unauthorized), Some(client)) = (&handshake, &mut refresher)
+                {
+                    match client.refresh().await {
+                        Ok(refreshed) => {
+                            token = refreshed;
+                            handshake = Connection::handshake(&url, &token).await;
+                        }
+                        Err(ClientError::Unauthorized | ClientError::NotLoggedIn) => {}
+                        Err(error) => handshake = Err(error),
+                    }
+                }
+                match handshake {
+                    Ok(ws) => break ws,
+                    Err(ClientError::Unauthorized) => {
+                        let _ = events.send(Err(ClientError::Unauthorized));
+                        return;
+                    }
+                    Err(error) => reason = Some(error.to_string()),
+                }
+            };
+
+            if let Some(frame) = resume.resume_frame() {
+                let text = serde_json::to_string(&frame).unwrap();
+                if let Err(e) = ws.send(ws::Message::Text(text.into())).await {
+                    log::debug!("Could not resume the previous channel: {:?}", e);
+                }
+            }
+        }
+    }
+
+    async fn pump(
+        ws: &mut Socket,
+        commands: &mut mpsc::UnboundedReceiver<ClientFrame>,
+        events: &mpsc::UnboundedSender<Result<ServerEvent, ClientError>>,
+        resume: &mut Resume,
+    ) -> Ended {
+        loop {
+            select! {
+                frame = ws.next() => match frame {
+                    Some(Ok(ws::Frame::Text(txt))) => {
+                        match serde_json::from_slice::<ServerFrame>(&txt) {
+                            Ok(frame) if frame.v == PROTOCOL_VERSION => {
+                                for event in resume.receive(frame.event) {
+                                    resume.track_event(&event);
+                                    let _ = events.send(Ok(event));
+                                }
+                            }
+                            Ok(frame) => log::debug!(
+                                "Ignoring frame with unsupported protocol version {}",
+                                frame.v
+                            ),
+                            Err(e) => log::debug!("Could not parse server frame: {:?}", e),
+                        }
+                    }
+                    Some(Ok(ws::Frame::Ping(msg))) => {
+                        let _ = ws.send(ws::Message::Pong(msg)).await;
+                    }
+                    Some(Ok(ws::Frame::Close(reason))) => {
+                        let fatal = matches!(
+                            &reason,
+                            Some(reason) if reason.code == CloseCode::Policy
+                        );
+                        let description = reason.and_then(|reason| reason.description);
+                        return match fatal {
+                            true => Ended::Fatal(description),
+                            false => Ended::Lost(description),
+                        };
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Ended::Lost(Some(e.to_string())),
+                    None => return Ended::Lost(None),
+                },
+                command = commands.recv() => match command {
+                    Some(frame) => {
+                        resume.track_command(&frame.command);
+                        let text = serde_json::to_string(&frame).unwrap();
+                        if let Err(e) = ws.send(ws::Message::Text(text.into())).await {
+                            return Ended::Lost(Some(e.to_string()));
+                        }
+                    }
+                    None => {
+                        let _ = ws.send(ws::Message::Close(None)).await;
+                        return Ended::ByClient;
+                    }
+                },
+            }
+        }
+    }
+
+    pub fn send(&mut self, command: ClientCommand) -> Result<u64, ClientError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.commands
+            .send(ClientFrame {
+                v: PROTOCOL_VERSION,
+                id: Some(id),
+                command,
+            })
+            .map_err(|_| ClientError::Closed(None))?;
+        Ok(id)
+    }
+
+    pub fn send_message(&mut self, text: &str) -> Result<u64, ClientError> {
+        self.send(ClientCommand::SendMessage {
+            text: text.to_owned(),
+        })
+    }
+
+    pub fn join_room(&mut self, room: &str, invite: Option<&str>) -> Result<u64, ClientError> {
+        self.send(ClientCommand::JoinRoom {
+            room: room.to_owned(),
+            invite: invite.map(|invite| invite.to_owned()),
+            since: None,
+        })
+    }
+
+    pub fn join_direct(&mut self, username: &str) -> Result<u64, ClientError> {
+        self.send(ClientCommand::JoinDirect {
+            username: username.to_owned(),
+            since: None,
+        })
+    }
+
+    pub fn whisper(&mut self, username: &str, text: &str) -> Result<u64, ClientError> {
+        self.send(ClientCommand::Whisper {
+            username: username.to_owned(),
+            text: text.to_owned(),
+        })
+    }
+
+    pub fn list_rooms(&mut self) -> Result<u64, ClientError> {
+        self.send(ClientCommand::ListRooms)
+    }
+
+    pub fn list_users_online(&mut self) -> Result<u64, ClientError> {
+        self.send(ClientCommand::ListUsersOnline)
+    }
+
+    pub fn list_users_in_room(&mut self) -> Result<u64, ClientError> {
+        self.send(ClientCommand::ListUsersInRoom)
+    }
+
+    pub fn list_direct_chats(&mut self) -> Result<u64, ClientError> {
+        self.send(ClientCommand::ListDirectChats)
+    }
+}
+
+impl Stream for Connection {
+    type Item = Result<ServerEvent, ClientError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_factory(text: &str, history: bool) -> ServerEvent {
+        cursor_message_factory(text, history, text, 1718000000000)
+    }
+
+    fn cursor_message_factory(
+        text: &str,
+        history: bool,
+        message_id: &str,
+        created_at: u64,
+    ) -> ServerEvent {
+        ServerEvent::MessageReceived {
+            sender: String::from("alice"),
+            channel: String::from("deploys"),
+            chat_type: ChatType::Room,
+            msg_type: termtalk_proto::chat::MessageType::Room,
+            text: text.to_owned(),
+            history,
+            queued: false,
+            message_id: Some(message_id.to_owned()),
+            created_at: Some(created_at),
+        }
+    }
+
+    fn joined_factory(channel: &str) -> ServerEvent {
+        ServerEvent::ChannelJoined {
+            channel: channel.to_owned(),
+            chat_type: ChatType::Room,
+        }
+    }
+
+    #[test]
+    fn test_reconnect_delay_backs_off_exponentially() {
+        let reconnect = Reconnect::default();
+        assert_eq!(Duration::from_millis(500), reconnect.delay(1));
+        assert_eq!(Duration::from_secs(1), reconnect.delay(2));
+        assert_eq!(Duration::from_secs(4), reconnect.delay(4));
+        assert_eq!(Duration::from_secs(30), reconnect.delay(10));
+        assert_eq!(Duration::from_secs(30), reconnect.delay(u32::MAX));
+    }
+
+    #[test]
+    fn test_resume_rejoins_last_channel() {
+        let mut resume = Resume::new();
+        assert_eq!(None, resume.resume_frame());
+
+        let event = joined_factory("deploys");
+        resume.track_event(&event);
+        let frame = resume.resume_frame().unwrap();
+        assert_eq!(Some(u64::MAX), frame.id);
+        assert_eq!(
+            ClientCommand::JoinRoom {
+                room: String::from("deploys"),
+                invite: None,
+                since: None,
+            },
+            frame.command
+        );
+
+        resume.track_command(&ClientCommand::JoinDirect {
+            username: String::from("bob"),
+            since: None,
+        });
+        resume.track_event(&ServerEvent::ChannelJoined {
+            channel: String::from("alice_bob"),
+            chat_type: ChatType::Direct,
+        });
+        let frame = resume.resume_frame().unwrap();
+        assert_eq!(Some(u64::MAX - 1), frame.id);
+        assert!(matches!(
+            frame.command,
+            ClientCommand::JoinDirect { username, since: None } if username == "bob"
+        ));
+    }
+
+    #[test]
+    fn test_resume_from_last_message_created_at() {
+        let mut resume = Resume::new();
+        resume.track_event(&joined_factory("deploys"));
+        resume.track_event(&cursor_message_factory("old", true, "m1", 1718000000100));
+        resume.track_event(&cursor_message_factory(
+            "shipped",
+            false,
+            "m2",
+            1718000000123,
+        ));
+        resume.track_event(&cursor_message_factory("late", false, "m0", 1718000000050));
+        resume.track_event(&ServerEvent::Notice {
+            text: String::from("hi"),
+        });
+
+        assert!(matches!(
+            resume.resume_frame().unwrap().command,
+            ClientCommand::JoinRoom {
+                since: Some(1718000000123),
+                ..
+            }
+        ));
+
+        resume.track_event(&joined_factory("ops"));
+        assert!(matches!(
+            resume.resume_frame().unwrap().command,
+            ClientCommand::JoinRoom { since: None, .. }
+        ));
+    }
+
+    #[test]
+    fn test_resume_drops_messages_already_seen() {
+        let mut resume = Resume::new();
+        resume.track_event(&joined_factory("deploys"));
+        resume.track_event(&cursor_message_factory("first", false, "m1", 1718000000123));
+        resume.track_event(&cursor_message_factory(
+            "second",
+            false,
+            "m2",
+            1718000000123,
+        ));
+        let id = resume.resume_frame().unwrap().id.unwrap();
+
+        for event in [
+            joined_factory("deploys"),
+            cursor_message_factory("first", true, "m1", 1718000000123),
+            cursor_message_factory("missed", false, "m3", 1718000000123),
+            cursor_message_factory("second", true, "m2", 1718000000123),
+            cursor_message_factory("missed", true, "m3", 1718000000123),
+        ] {
+            assert!(resume.receive(event).is_empty());
+        }
+
+        assert_eq!(
+            format!(
+                "{:?}",
+                vec![
+                    joined_factory("deploys"),
+                    cursor_message_factory("missed", false, "m3", 1718000000123),
+                ]
+            ),
+            format!("{:?}", resume.receive(ServerEvent::Ack { id }))
+        );
+    }
+
+    #[test]
+    fn test_resume_drops_default_room_scrollback() {
+        let mut resume = Resume::new();
+        resume.track_event(&joined_factory("deploys"));
+        let id = resume.resume_frame().unwrap().id.unwrap();
+
+        for event in [
+            joined_factory("Main"),
+            message_factory("old news", true),
+            message_factory("live", false),
+            joined_factory("deploys"),
+            message_factory("missed", true),
+        ] {
+            assert!(resume.receive(event).is_empty());
+        }
+
+        assert_eq!(
+            format!(
+                "{:?}",
+                vec![
+                    message_factory("live", false),
+                    joined_factory("deploys"),
+                    message_factory("missed", true),
+                ]
+            ),
+            format!("{:?}", resume.receive(ServerEvent::Ack { id }))
+        );
+        assert_eq!(1, resume.receive(message_factory("after", false)).len());
+    }
+}
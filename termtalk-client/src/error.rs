@@ -0,0 +1,67 @@
+use std::time::Duration;
+use std::{error::Error, fmt};
+
+#[derive(Debug, PartialEq)]
+pub enum ClientError {
+    Request(String),
+    Rejected(String),
+    NotLoggedIn,
+    Unauthorized,
+    Connect(String),
+    Closed(Option<String>),
+    Disconnected {
+        reason: Option<String>,
+        attempt: u32,
+        retry_in: Duration,
+    },
+}
+
+impl ClientError {
+    pub fn is_reconnecting(&self) -> bool {
+        matches!(self, ClientError::Disconnected { .. })
+    }
+}
+
+impl Error for ClientError {}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::Request(error) => {
+                write!(f, "Could not reach the termtalk-api server: {}", error)
+            }
+            ClientError::Rejected(reason) => write!(f, "Request was rejected: {}", reason),
+            ClientError::NotLoggedIn => write!(f, "Log in before connecting"),
+            ClientError::Unauthorized => write!(f, "The server did not accept the login token"),
+            ClientError::Connect(error) => {
+                write!(f, "Could not connect to the termtalk-api server: {}", error)
+            }
+            ClientError::Closed(Some(reason)) => {
+                write!(f, "Server closed the connection because {}", reason)
+            }
+            ClientError::Closed(None) => write!(f, "Server closed the connection"),
+            ClientError::Disconnected {
+                reason,
+                attempt,
+                retry_in,
+            } => {
+                write!(f, "Lost the connection to the server")?;
+                if let Some(reason) = reason {
+                    write!(f, " because {}", reason)?;
+                }
+                write!(
+                    f,
+                    ", reconnecting in {:.1}s (attempt {})",
+                    retry_in.as_secs_f32(),
+                    attempt
+                )
+            }
+        }
+    }
+}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(error: reqwest::Error) -> Self {
+        ClientError::Request(error.to_string())
+    }
+}
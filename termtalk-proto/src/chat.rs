@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Presence {
+    Joined,
+    Left,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq)]
+pub enum ChatType {
+    Direct,
+    Room,
+    Whisper,
+    NoPreviousChatType,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq)]
+pub enum MessageType {
+    Direct,
+    Room,
+    Whisper,
+    Server,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct QueueMessage {
+    pub sender: String,
+    pub chat_type: ChatType,
+    pub msg_type: MessageType,
+    pub recipient: String,
+    pub msg: String,
+    #[serde(default)]
+    pub presence: Option<Presence>,
+    #[serde(default)]
+    pub message_id: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<u64>,
+}
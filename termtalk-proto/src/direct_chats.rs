@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+pub static DIRECT_CHANNEL_SEPARATOR: &str = "_";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DirectChatSummary {
+    pub name: String,
+    pub with: String,
+    pub last_activity_at: u64,
+    pub unread: usize,
+}
@@ -0,0 +1,6 @@
+pub mod chat;
+pub mod direct_chats;
+pub mod messages;
+pub mod protocol;
+pub mod request_models;
+pub mod rooms;
@@ -0,0 +1,14 @@
+use crate::chat::{ChatType, MessageType};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MessageSearchHit {
+    pub message_id: String,
+    pub sender: String,
+    pub channel: String,
+    pub chat_type: ChatType,
+    pub msg_type: MessageType,
+    pub msg: String,
+    pub created_at: u64,
+    pub score: Option<f64>,
+}
@@ -0,0 +1,476 @@
+use crate::chat::{ChatType, MessageType};
+use crate::direct_chats::DirectChatSummary;
+use crate::messages::MessageSearchHit;
+use crate::rooms::{ModerationAction, RoomRole, RoomSummary};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt};
+
+pub static PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ClientFrame {
+    pub v: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    #[serde(flatten)]
+    pub command: ClientCommand,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientCommand {
+    SendMessage {
+        text: String,
+    },
+    JoinRoom {
+        room: String,
+        #[serde(default)]
+        invite: Option<String>,
+        #[serde(default)]
+        since: Option<u64>,
+    },
+    JoinDirect {
+        username: String,
+        #[serde(default)]
+        since: Option<u64>,
+    },
+    Whisper {
+        username: String,
+        text: String,
+    },
+    ListRooms,
+    ListUsersOnline,
+    ListUsersInRoom,
+    ListDirectChats,
+    Search {
+        query: String,
+    },
+    WhoAmI,
+    CreateRoom {
+        room: String,
+        #[serde(default)]
+        private: bool,
+    },
+    CreateRoomInvite {
+        room: String,
+    },
+    RenameRoom {
+        room: String,
+        new_room: String,
+    },
+    ArchiveRoom {
+        room: String,
+    },
+    DeleteRoom {
+        room: String,
+    },
+    SetRoomRole {
+        room: String,
+        username: String,
+        role: RoomRole,
+    },
+    Moderate {
+        action: ModerationAction,
+        username: String,
+    },
+    Debug,
+    DebugServer,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ServerFrame {
+    pub v: u32,
+    #[serde(flatten)]
+    pub event: ServerEvent,
+}
+
+impl ServerFrame {
+    pub fn new(event: ServerEvent) -> Self {
+        ServerFrame {
+            v: PROTOCOL_VERSION,
+            event,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerEvent {
+    MessageReceived {
+        sender: String,
+        channel: String,
+        chat_type: ChatType,
+        msg_type: MessageType,
+        text: String,
+        history: bool,
+        queued: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        created_at: Option<u64>,
+    },
+    Notice {
+        text: String,
+    },
+    UserJoined {
+        username: String,
+        channel: String,
+        chat_type: ChatType,
+    },
+    UserLeft {
+        username: String,
+        channel: String,
+        chat_type: ChatType,
+    },
+    ChannelJoined {
+        channel: String,
+        chat_type: ChatType,
+    },
+    Identity {
+        username: String,
+        channel: String,
+        chat_type: ChatType,
+    },
+    UserList {
+        channel: Option<String>,
+        users: Vec<String>,
+    },
+    RoomList {
+        rooms: Vec<RoomSummary>,
+    },
+    DirectChatList {
+        direct_chats: Vec<DirectChatSummary>,
+    },
+    SearchResults {
+        query: String,
+        hits: Vec<MessageSearchHit>,
+    },
+    RoomInvite {
+        room: String,
+        code: String,
+        expires_in: usize,
+    },
+    Error {
+        id: Option<u64>,
+        code: ErrorCode,
+        message: String,
+    },
+    Ack {
+        id: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    BadRequest,
+    UnknownCommand,
+    UnsupportedVersion,
+    NotFound,
+    AlreadyExists,
+    Forbidden,
+    Archived,
+    Banned,
+    Muted,
+    InvalidInvite,
+    UserNotFound,
+    Internal,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CommandError {
+    Usage(String),
+    Unknown(String),
+}
+
+impl CommandError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            CommandError::Usage(_) => ErrorCode::BadRequest,
+            CommandError::Unknown(_) => ErrorCode::UnknownCommand,
+        }
+    }
+}
+
+impl Error for CommandError {}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandError::Usage(usage) => write!(f, "{}", usage),
+            CommandError::Unknown(command) => write!(
+                f,
+                "Unknown command {}. Type /help for a list of commands",
+                command
+            ),
+        }
+    }
+}
+
+fn usage(text: &str) -> CommandError {
+    CommandError::Usage(text.to_owned())
+}
+
+impl ClientCommand {
+    pub fn parse_text(text: &str) -> Result<ClientCommand, CommandError> {
+        let text = text.trim();
+        if !text.starts_with('/') {
+            return Ok(ClientCommand::SendMessage {
+                text: text.to_owned(),
+            });
+        }
+
+        let (command, rest) = match text.split_once(' ') {
+            Some((command, rest)) => (command, rest.trim()),
+            None => (text, ""),
+        };
+        let args: Vec<&str> = rest.split_whitespace().collect();
+
+        match command {
+            "/debug" => Ok(ClientCommand::Debug),
+            "/debug_server" => Ok(ClientCommand::DebugServer),
+            "/whoami" => Ok(ClientCommand::WhoAmI),
+            "/o" | "/online" => Ok(ClientCommand::ListUsersOnline),
+            "/r" | "/rooms" => Ok(ClientCommand::ListRooms),
+            "/h" | "/here" => Ok(ClientCommand::ListUsersInRoom),
+            "/dms" => Ok(ClientCommand::ListDirectChats),
+            "/j" | "/join" => match args.as_slice() {
+                [room] => Ok(ClientCommand::JoinRoom {
+                    room: room.to_string(),
+                    invite: None,
+                    since: None,
+                }),
+                [room, invite] => Ok(ClientCommand::JoinRoom {
+                    room: room.to_string(),
+                    invite: Some(invite.to_string()),
+                    since: None,
+                }),
+                _ => Err(usage(
+                    "/j | /join command requires a room name, optionally followed by an invite code. Try again",
+                )),
+            },
+            "/d" | "/direct" => match args.as_slice() {
+                [username] => Ok(ClientCommand::JoinDirect {
+                    username: username.to_string(),
+                    since: None,
+                }),
+                _ => Err(usage(
+                    "/d | /direct command requires that you pass in a username as an argument. Try again",
+                )),
+            },
+            "/w" | "/whisper" => match rest.split_once(' ') {
+                Some((username, text)) if !text.trim().is_empty() => Ok(ClientCommand::Whisper {
+                    username: username.to_owned(),
+                    text: text.trim().to_owned(),
+                }),
+                _ => Err(usage(
+                    "/w | /whisper command requires that you pass in a username as an argument followed by a message. Try again",
+                )),
+            },
+            "/s" | "/search" => {
+                if rest.is_empty() {
+                    return Err(usage(
+                        "/s | /search command requires a query, for example: /search from:alice in:Main deploy",
+                    ));
+                }
+                Ok(ClientCommand::Search {
+                    query: rest.to_owned(),
+                })
+            }
+            "/room" => ClientCommand::parse_room_command(&args),
+            "/kick" | "/ban" | "/unban" | "/mute" | "/unmute" => {
+                let action = match command {
+                    "/kick" => ModerationAction::Kick,
+                    "/ban" => ModerationAction::Ban,
+                    "/unban" => ModerationAction::Unban,
+                    "/mute" => ModerationAction::Mute,
+                    _ => ModerationAction::Unmute,
+                };
+                match args.as_slice() {
+                    [username] => Ok(ClientCommand::Moderate {
+                        action,
+                        username: username.to_string(),
+                    }),
+                    _ => Err(usage(
+                        "/kick, /ban, /unban, /mute and /unmute require a username as an argument. Try again",
+                    )),
+                }
+            }
+            _ => Err(CommandError::Unknown(command.to_owned())),
+        }
+    }
+
+    fn parse_room_command(args: &[&str]) -> Result<ClientCommand, CommandError> {
+        match args {
+            ["create", room] => Ok(ClientCommand::CreateRoom {
+                room: room.to_string(),
+                private: false,
+            }),
+            ["create", room, "private"] => Ok(ClientCommand::CreateRoom {
+                room: room.to_string(),
+                private: true,
+            }),
+            ["invite", room] => Ok(ClientCommand::CreateRoomInvite {
+                room: room.to_string(),
+            }),
+            ["rename", room, new_room] => Ok(ClientCommand::RenameRoom {
+                room: room.to_string(),
+                new_room: new_room.to_string(),
+            }),
+            ["archive", room] => Ok(ClientCommand::ArchiveRoom {
+                room: room.to_string(),
+            }),
+            ["delete", room] => Ok(ClientCommand::DeleteRoom {
+                room: room.to_string(),
+            }),
+            ["promote", room, username] => Ok(ClientCommand::SetRoomRole {
+                room: room.to_string(),
+                username: username.to_string(),
+                role: RoomRole::Moderator,
+            }),
+            ["demote", room, username] => Ok(ClientCommand::SetRoomRole {
+                room: room.to_string(),
+                username: username.to_string(),
+                role: RoomRole::Member,
+            }),
+            _ => Err(usage(
+                "/room command usage: /room create (room) [private] | /room invite (room) | /room rename (room) (new name) | /room archive (room) | /room delete (room) | /room promote (room) (user) | /room demote (room) (user)",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text_plain_message() {
+        assert_eq!(
+            Ok(ClientCommand::SendMessage {
+                text: String::from("hello there")
+            }),
+            ClientCommand::parse_text("  hello there ")
+        );
+    }
+
+    #[test]
+    fn test_parse_text_join_with_invite() {
+        assert_eq!(
+            Ok(ClientCommand::JoinRoom {
+                room: String::from("ops"),
+                invite: Some(String::from("a1b2c3")),
+                since: None,
+            }),
+            ClientCommand::parse_text("/join ops a1b2c3")
+        );
+    }
+
+    #[test]
+    fn test_parse_text_whisper_keeps_message_spaces() {
+        assert_eq!(
+            Ok(ClientCommand::Whisper {
+                username: String::from("bob"),
+                text: String::from("see you at 5"),
+            }),
+            ClientCommand::parse_text("/w bob see you at 5")
+        );
+    }
+
+    #[test]
+    fn test_parse_text_room_and_moderation() {
+        assert_eq!(
+            Ok(ClientCommand::CreateRoom {
+                room: String::from("ops"),
+                private: true,
+            }),
+            ClientCommand::parse_text("/room create ops private")
+        );
+        assert_eq!(
+            Ok(ClientCommand::Moderate {
+                action: ModerationAction::Ban,
+                username: String::from("mallory"),
+            }),
+            ClientCommand::parse_text("/ban mallory")
+        );
+    }
+
+    #[test]
+    fn test_parse_text_errors() {
+        assert_eq!(
+            Err(CommandError::Unknown(String::from("/dance"))),
+            ClientCommand::parse_text("/dance")
+        );
+        assert_eq!(
+            ErrorCode::BadRequest,
+            ClientCommand::parse_text("/whisper bob")
+                .unwrap_err()
+                .code()
+        );
+    }
+
+    #[test]
+    fn test_client_frame_round_trip() {
+        let frame: ClientFrame =
+            serde_json::from_str(r#"{"v":1,"id":7,"type":"join_room","room":"ops","invite":null}"#)
+                .unwrap();
+        assert_eq!(
+            ClientFrame {
+                v: 1,
+                id: Some(7),
+                command: ClientCommand::JoinRoom {
+                    room: String::from("ops"),
+                    invite: None,
+                    since: None,
+                },
+            },
+            frame
+        );
+
+        let frame: ClientFrame = serde_json::from_str(
+            r#"{"v":1,"type":"join_direct","username":"bob","since":1700000000000}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            ClientCommand::JoinDirect {
+                username: String::from("bob"),
+                since: Some(1700000000000),
+            },
+            frame.command
+        );
+
+        let frame: ClientFrame = serde_json::from_str(r#"{"v":1,"type":"list_rooms"}"#).unwrap();
+        assert_eq!(ClientCommand::ListRooms, frame.command);
+    }
+
+    #[test]
+    fn test_server_frame_is_tagged_and_versioned() {
+        let frame = ServerFrame::new(ServerEvent::Notice {
+            text: String::from("hi"),
+        });
+        assert_eq!(
+            r#"{"v":1,"type":"notice","text":"hi"}"#,
+            serde_json::to_string(&frame).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_server_frame_round_trip() {
+        let frame = ServerFrame::new(ServerEvent::Error {
+            id: Some(3),
+            code: ErrorCode::NotFound,
+            message: String::from("Room Ops does not exist"),
+        });
+        let frame: ServerFrame =
+            serde_json::from_str(&serde_json::to_string(&frame).unwrap()).unwrap();
+
+        assert_eq!(PROTOCOL_VERSION, frame.v);
+        match frame.event {
+            ServerEvent::Error { id, code, .. } => {
+                assert_eq!(Some(3), id);
+                assert_eq!(ErrorCode::NotFound, code);
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+    }
+}
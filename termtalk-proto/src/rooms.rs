@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RoomSummary {
+    pub name: String,
+    pub online_users: usize,
+    pub archived: bool,
+    pub private: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum RoomRole {
+    Member,
+    Moderator,
+    Owner,
+}
+
+impl RoomRole {
+    pub fn can_moderate(&self, target: &RoomRole) -> bool {
+        *self >= RoomRole::Moderator && self > target
+    }
+}
+
+impl fmt::Display for RoomRole {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RoomRole::Member => write!(f, "member"),
+            RoomRole::Moderator => write!(f, "moderator"),
+            RoomRole::Owner => write!(f, "owner"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationAction {
+    Kick,
+    Ban,
+    Unban,
+    Mute,
+    Unmute,
+}
+
+impl ModerationAction {
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            ModerationAction::Kick => "kicked",
+            ModerationAction::Ban => "banned",
+            ModerationAction::Unban => "unbanned",
+            ModerationAction::Mute => "muted",
+            ModerationAction::Unmute => "unmuted",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_room_role_can_moderate() {
+        assert!(RoomRole::Owner.can_moderate(&RoomRole::Moderator));
+        assert!(RoomRole::Owner.can_moderate(&RoomRole::Member));
+        assert!(RoomRole::Moderator.can_moderate(&RoomRole::Member));
+    }
+
+    #[test]
+    fn test_room_role_cannot_moderate_peers_or_superiors() {
+        assert!(!RoomRole::Member.can_moderate(&RoomRole::Member));
+        assert!(!RoomRole::Moderator.can_moderate(&RoomRole::Moderator));
+        assert!(!RoomRole::Moderator.can_moderate(&RoomRole::Owner));
+        assert!(!RoomRole::Owner.can_moderate(&RoomRole::Owner));
+    }
+}